@@ -0,0 +1,24 @@
+use monty_hall::game_tree::GameTree;
+use monty_hall::games::{Host, MontyHall, Placement};
+use monty_hall::matrix_game::solve_zero_sum;
+
+fn main() {
+    let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Host));
+    let game = tree.to_matrix();
+    let equilibrium = solve_zero_sum(&game);
+
+    println!("{game}");
+    println!("value: {:.4}", equilibrium.value);
+    println!("contestant:");
+    for (label, p) in game.row_labels().iter().zip(&equilibrium.first) {
+        if *p > 1e-9 {
+            println!("  {p:.4}  {label}");
+        }
+    }
+    println!("host:");
+    for (label, p) in game.col_labels().iter().zip(&equilibrium.second) {
+        if *p > 1e-9 {
+            println!("  {p:.4}  {label}");
+        }
+    }
+}
@@ -0,0 +1,247 @@
+use proc_macro::TokenStream;
+use proc_macro2::TokenStream as TokenStream2;
+use quote::{format_ident, quote};
+use syn::parse::Parse;
+use syn::{
+    parenthesized, parse_macro_input, Attribute, Data, DeriveInput, Error, Fields, Ident, Meta,
+    Token,
+};
+
+/// Generates the types of what the first and the second player observe of a move enum, with the
+/// `From` conversions hiding what each player is not allowed to see
+///
+/// A variant or a field annotated with `#[observation(first)]`, `#[observation(second)]` or
+/// `#[observation(none)]` is only visible to the given players; everything else is visible to
+/// both. A variant hidden from a player becomes the `Hidden` variant of their observation type,
+/// while a visible variant keeps its name and its visible fields, so hiding all the fields of a
+/// variant only reveals that the move happened.
+///
+/// The observation types are named `<Move>FirstView` and `<Move>SecondView` unless the enum is
+/// annotated with `#[observation(first = FirstName, second = SecondName)]`; they derive `Debug`,
+/// `Copy`, `Clone`, `Eq`, `PartialEq`, `Hash`, and `Default` when a variant is hidden, and
+/// `#[observation(attrs(...))]` adds more attributes to both.
+///
+/// ```ignore
+/// #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Observation)]
+/// #[observation(first = Hand, second = Opponent, attrs(derive(Ord, PartialOrd)))]
+/// enum Deal {
+///     Cards {
+///         #[observation(first)]
+///         first: u8,
+///         #[observation(second)]
+///         second: u8,
+///     },
+///     #[observation(first)]
+///     Peek(u8),
+///     Bet,
+/// }
+/// ```
+#[proc_macro_derive(Observation, attributes(observation))]
+pub fn derive_observation(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    expand(&input)
+        .unwrap_or_else(Error::into_compile_error)
+        .into()
+}
+
+/// Whether the first and the second player see the annotated variant or field
+fn visibility(attrs: &[Attribute]) -> syn::Result<[bool; 2]> {
+    let mut ans = None;
+
+    for attr in attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("observation"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let seen = if meta.path.is_ident("first") {
+                [true, false]
+            } else if meta.path.is_ident("second") {
+                [false, true]
+            } else if meta.path.is_ident("both") {
+                [true, true]
+            } else if meta.path.is_ident("none") {
+                [false, false]
+            } else {
+                return Err(meta.error("expected first, second, both or none"));
+            };
+
+            if ans.replace(seen).is_some() {
+                return Err(meta.error("the visibility is given more than once"));
+            }
+            Ok(())
+        })?;
+    }
+
+    Ok(ans.unwrap_or([true, true]))
+}
+
+/// Names of the observation types of both players and the attributes added to them
+fn views(input: &DeriveInput) -> syn::Result<([Ident; 2], Vec<Meta>)> {
+    let mut views = [
+        format_ident!("{}FirstView", input.ident),
+        format_ident!("{}SecondView", input.ident),
+    ];
+    let mut attrs = vec![];
+
+    for attr in input
+        .attrs
+        .iter()
+        .filter(|attr| attr.path().is_ident("observation"))
+    {
+        attr.parse_nested_meta(|meta| {
+            let player = if meta.path.is_ident("first") {
+                0
+            } else if meta.path.is_ident("second") {
+                1
+            } else if meta.path.is_ident("attrs") {
+                let content;
+                parenthesized!(content in meta.input);
+                attrs.extend(content.parse_terminated(Meta::parse, Token![,])?);
+                return Ok(());
+            } else {
+                return Err(meta.error("expected first = Name, second = Name or attrs(...)"));
+            };
+
+            views[player] = meta.value()?.parse()?;
+            Ok(())
+        })?;
+    }
+
+    Ok((views, attrs))
+}
+
+fn expand(input: &DeriveInput) -> syn::Result<TokenStream2> {
+    let Data::Enum(data) = &input.data else {
+        return Err(Error::new_spanned(
+            &input.ident,
+            "observations can only be derived for enums",
+        ));
+    };
+
+    if !input.generics.params.is_empty() {
+        return Err(Error::new_spanned(
+            &input.generics,
+            "generic move types are not supported",
+        ));
+    }
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let (views, attrs) = views(input)?;
+    let mut ans = TokenStream2::new();
+
+    for (player, view) in views.iter().enumerate() {
+        let mut variants = vec![];
+        let mut arms = vec![];
+        let mut hidden = false;
+
+        for variant in &data.variants {
+            let ident = &variant.ident;
+
+            if ident == "Hidden" {
+                return Err(Error::new_spanned(
+                    ident,
+                    "Hidden is reserved for the moves a player does not see",
+                ));
+            }
+
+            if !visibility(&variant.attrs)?[player] {
+                hidden = true;
+                arms.push(quote! { #name::#ident { .. } => Self::Hidden });
+                continue;
+            }
+
+            match &variant.fields {
+                Fields::Unit => {
+                    variants.push(quote! { #ident });
+                    arms.push(quote! { #name::#ident => Self::#ident });
+                }
+                Fields::Named(fields) => {
+                    let mut names = vec![];
+                    let mut types = vec![];
+
+                    for field in &fields.named {
+                        if visibility(&field.attrs)?[player] {
+                            names.push(&field.ident);
+                            types.push(&field.ty);
+                        }
+                    }
+
+                    if names.is_empty() {
+                        variants.push(quote! { #ident });
+                        arms.push(quote! { #name::#ident { .. } => Self::#ident });
+                    } else {
+                        variants.push(quote! { #ident { #(#names: #types),* } });
+                        arms.push(quote! {
+                            #name::#ident { #(#names,)* .. } => Self::#ident { #(#names),* }
+                        });
+                    }
+                }
+                Fields::Unnamed(fields) => {
+                    let mut patterns = vec![];
+                    let mut bindings = vec![];
+                    let mut types = vec![];
+
+                    for (k, field) in fields.unnamed.iter().enumerate() {
+                        if visibility(&field.attrs)?[player] {
+                            let binding = format_ident!("x{k}");
+
+                            patterns.push(quote! { #binding });
+                            bindings.push(binding);
+                            types.push(&field.ty);
+                        } else {
+                            patterns.push(quote! { _ });
+                        }
+                    }
+
+                    if bindings.is_empty() {
+                        variants.push(quote! { #ident });
+                        arms.push(quote! { #name::#ident(..) => Self::#ident });
+                    } else {
+                        variants.push(quote! { #ident(#(#types),*) });
+                        arms.push(quote! {
+                            #name::#ident(#(#patterns),*) => Self::#ident(#(#bindings),*)
+                        });
+                    }
+                }
+            }
+        }
+
+        let doc = format!(
+            "What the {} player observes of a [`{name}`]",
+            ["first", "second"][player]
+        );
+        let (derive_default, hidden) = if hidden {
+            (
+                quote! { Default, },
+                quote! {
+                    #[default]
+                    Hidden,
+                },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        ans.extend(quote! {
+            #[doc = #doc]
+            #[derive(Debug, #derive_default Copy, Clone, Eq, PartialEq, Hash)]
+            #(#[#attrs])*
+            #vis enum #view {
+                #hidden
+                #(#variants),*
+            }
+
+            impl From<#name> for #view {
+                fn from(value: #name) -> Self {
+                    match value {
+                        #(#arms),*
+                    }
+                }
+            }
+        });
+    }
+
+    Ok(ans)
+}
@@ -0,0 +1,70 @@
+use monty_hall_derive::Observation;
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Observation)]
+enum Deal {
+    Cards {
+        #[observation(first)]
+        first: u8,
+        #[observation(second)]
+        second: u8,
+    },
+    #[observation(first)]
+    Peek(u8, #[observation(none)] bool),
+    #[observation(second)]
+    Shown {
+        card: u8,
+    },
+    Bet,
+}
+
+#[derive(Debug, Copy, Clone, Eq, PartialEq, Hash, Observation)]
+#[observation(first = Row, second = Column, attrs(derive(Ord, PartialOrd)))]
+enum Pick {
+    #[observation(first)]
+    Row(usize),
+    #[observation(both)]
+    Column(usize),
+}
+
+#[test]
+fn hidden_fields_and_variants() {
+    let cards = Deal::Cards {
+        first: 1,
+        second: 2,
+    };
+
+    assert_eq!(
+        DealFirstView::from(cards),
+        DealFirstView::Cards { first: 1 }
+    );
+    assert_eq!(
+        DealSecondView::from(cards),
+        DealSecondView::Cards { second: 2 }
+    );
+    assert_eq!(
+        DealFirstView::from(Deal::Peek(3, true)),
+        DealFirstView::Peek(3)
+    );
+    assert_eq!(
+        DealSecondView::from(Deal::Peek(3, true)),
+        DealSecondView::Hidden
+    );
+    assert_eq!(
+        DealFirstView::from(Deal::Shown { card: 4 }),
+        DealFirstView::Hidden
+    );
+    assert_eq!(
+        DealSecondView::from(Deal::Shown { card: 4 }),
+        DealSecondView::Shown { card: 4 }
+    );
+    assert_eq!(DealFirstView::from(Deal::Bet), DealFirstView::Bet);
+    assert_eq!(DealFirstView::default(), DealFirstView::Hidden);
+}
+
+#[test]
+fn custom_names_and_attributes() {
+    assert_eq!(Row::from(Pick::Row(1)), Row::Row(1));
+    assert_eq!(Row::from(Pick::Column(1)), Row::Column(1));
+    assert_eq!(Column::from(Pick::Row(1)), Column::Hidden);
+    assert!(Column::Hidden < Column::Column(0));
+}
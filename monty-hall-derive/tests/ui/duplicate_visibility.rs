@@ -0,0 +1,10 @@
+use monty_hall_derive::Observation;
+
+#[derive(Observation)]
+enum Deal {
+    #[observation(first)]
+    #[observation(second)]
+    Card(u8),
+}
+
+fn main() {}
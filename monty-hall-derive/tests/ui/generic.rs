@@ -0,0 +1,8 @@
+use monty_hall_derive::Observation;
+
+#[derive(Observation)]
+enum Deal<T> {
+    Card(T),
+}
+
+fn main() {}
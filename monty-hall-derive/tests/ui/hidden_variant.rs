@@ -0,0 +1,9 @@
+use monty_hall_derive::Observation;
+
+#[derive(Observation)]
+enum Deal {
+    Card(u8),
+    Hidden,
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+use monty_hall_derive::Observation;
+
+#[derive(Observation)]
+struct Deal {
+    card: u8,
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+use monty_hall_derive::Observation;
+
+#[derive(Observation)]
+enum Deal {
+    #[observation(third)]
+    Card(u8),
+}
+
+fn main() {}
@@ -0,0 +1,107 @@
+use good_lp::ResolutionError;
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum MontyError {
+    /// The linear program has no feasible solution
+    Infeasible,
+    /// The linear program has no finite optimum
+    Unbounded,
+    /// The solver gave up or returned an inconsistent solution
+    Solver(String),
+    /// A matrix row has a different length than the first one
+    RaggedMatrix {
+        row: usize,
+        expected: usize,
+        found: usize,
+    },
+    /// Matrices that must have the same shape do not
+    ShapeMismatch,
+    /// Probabilities of a random event are negative or do not sum up to one
+    BadProbabilities(f64),
+    /// A strategy returned a move that is not allowed by the rules
+    IllegalMove,
+    /// The rules returned no moves for a player or a random event
+    NoMoves,
+    /// A file is malformed or describes an unsupported game
+    Parse(String),
+    /// A type-erased strategy does not have the types of the game
+    ForeignStrategy,
+    /// No game is registered under the name
+    UnknownGame(String),
+    /// A game parameter has a malformed or unsupported value
+    BadParameter { name: String, message: String },
+    /// Reading the input or writing the output failed
+    Io(String),
+    /// A player forgets their own moves: one of their information sets is reached by different
+    /// sequences of them, or offers different moves
+    ImperfectRecall,
+    /// The payoffs of some outcome do not sum up to zero
+    NotZeroSum,
+    /// The rules gave the move to a player that does not exist
+    UnknownPlayer(usize),
+    /// There are not as many payoffs or strategies as players
+    PlayerCount { expected: usize, found: usize },
+}
+
+impl Display for MontyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MontyError::Infeasible => write!(f, "the linear program is infeasible"),
+            MontyError::Unbounded => write!(f, "the linear program is unbounded"),
+            MontyError::Solver(s) => write!(f, "solver failure: {s}"),
+            MontyError::RaggedMatrix {
+                row,
+                expected,
+                found,
+            } => write!(
+                f,
+                "row {row} of the matrix has {found} entries instead of {expected}"
+            ),
+            MontyError::ShapeMismatch => write!(f, "the matrices have different shapes"),
+            MontyError::BadProbabilities(sum) => write!(
+                f,
+                "probabilities of a random event sum up to {sum} instead of 1"
+            ),
+            MontyError::IllegalMove => write!(f, "a strategy made an illegal move"),
+            MontyError::NoMoves => write!(f, "no moves are available"),
+            MontyError::Parse(s) => write!(f, "parse error: {s}"),
+            MontyError::ForeignStrategy => write!(f, "the strategy belongs to another game"),
+            MontyError::UnknownGame(name) => write!(f, "unknown game {name}"),
+            MontyError::BadParameter { name, message } => {
+                write!(f, "bad parameter {name}: {message}")
+            }
+            MontyError::Io(s) => write!(f, "input/output error: {s}"),
+            MontyError::ImperfectRecall => write!(f, "the game does not have perfect recall"),
+            MontyError::NotZeroSum => write!(f, "the game is not zero-sum"),
+            MontyError::UnknownPlayer(player) => write!(f, "there is no player {player}"),
+            MontyError::PlayerCount { expected, found } => {
+                write!(
+                    f,
+                    "expected one entry per player ({expected}), found {found}"
+                )
+            }
+        }
+    }
+}
+
+impl Error for MontyError {}
+
+impl From<ResolutionError> for MontyError {
+    fn from(value: ResolutionError) -> Self {
+        match value {
+            ResolutionError::Unbounded => MontyError::Unbounded,
+            ResolutionError::Infeasible => MontyError::Infeasible,
+            ResolutionError::Other(s) => MontyError::Solver(s.to_string()),
+            ResolutionError::Str(s) => MontyError::Solver(s),
+        }
+    }
+}
+
+impl From<io::Error> for MontyError {
+    fn from(value: io::Error) -> Self {
+        MontyError::Io(value.to_string())
+    }
+}
@@ -0,0 +1,215 @@
+//! Reading and writing the file formats of [Gambit](https://www.gambit-project.org)
+
+use crate::error::MontyError;
+
+mod efg;
+mod nfg;
+
+pub use efg::EfgGame;
+
+#[derive(Debug, Clone, PartialEq)]
+enum Token {
+    Text(String),
+    Word(String),
+    Open,
+    Close,
+}
+
+/// Quoted string as written in the Gambit files
+pub(crate) fn quote(s: &str) -> String {
+    format!("\"{}\"", s.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+fn parse_error(message: impl Into<String>) -> MontyError {
+    MontyError::Parse(message.into())
+}
+
+struct Tokens {
+    tokens: Vec<Token>,
+    position: usize,
+}
+
+impl Tokens {
+    fn new(input: &str) -> Result<Self, MontyError> {
+        let mut tokens = vec![];
+        let mut chars = input.chars().peekable();
+
+        while let Some(c) = chars.next() {
+            match c {
+                '{' => tokens.push(Token::Open),
+                '}' => tokens.push(Token::Close),
+                '"' => {
+                    let mut text = String::new();
+                    loop {
+                        match chars.next() {
+                            None => return Err(parse_error("unterminated string")),
+                            Some('"') => break,
+                            Some('\\') => {
+                                text.extend(chars.next());
+                            }
+                            Some(c) => text.push(c),
+                        }
+                    }
+                    tokens.push(Token::Text(text));
+                }
+                c if c.is_whitespace() || c == ',' => {}
+                c => {
+                    let mut word = String::from(c);
+                    while let Some(c) =
+                        chars.next_if(|c| !c.is_whitespace() && !matches!(c, '{' | '}' | '"' | ','))
+                    {
+                        word.push(c);
+                    }
+                    tokens.push(Token::Word(word));
+                }
+            }
+        }
+
+        Ok(Self {
+            tokens,
+            position: 0,
+        })
+    }
+
+    fn peek(&self) -> Option<&Token> {
+        self.tokens.get(self.position)
+    }
+
+    fn next(&mut self) -> Result<Token, MontyError> {
+        let token = self
+            .tokens
+            .get(self.position)
+            .cloned()
+            .ok_or_else(|| parse_error("unexpected end of file"))?;
+        self.position += 1;
+        Ok(token)
+    }
+
+    fn is_done(&self) -> bool {
+        self.position == self.tokens.len()
+    }
+
+    fn expect(&mut self, expected: Token) -> Result<(), MontyError> {
+        let token = self.next()?;
+
+        if token == expected {
+            Ok(())
+        } else {
+            Err(parse_error(format!(
+                "expected {expected:?}, found {token:?}"
+            )))
+        }
+    }
+
+    /// Consumes the next token if it is `expected`
+    fn accept(&mut self, expected: &Token) -> bool {
+        if self.peek() == Some(expected) {
+            self.position += 1;
+            true
+        } else {
+            false
+        }
+    }
+
+    fn word(&mut self) -> Result<String, MontyError> {
+        match self.next()? {
+            Token::Word(word) => Ok(word),
+            token => Err(parse_error(format!("expected a word, found {token:?}"))),
+        }
+    }
+
+    fn text(&mut self) -> Result<String, MontyError> {
+        match self.next()? {
+            Token::Text(text) => Ok(text),
+            token => Err(parse_error(format!("expected a string, found {token:?}"))),
+        }
+    }
+
+    fn optional_text(&mut self) -> Option<String> {
+        match self.peek() {
+            Some(Token::Text(text)) => {
+                let text = text.clone();
+                self.position += 1;
+                Some(text)
+            }
+            _ => None,
+        }
+    }
+
+    fn index(&mut self) -> Result<usize, MontyError> {
+        let word = self.word()?;
+
+        word.parse()
+            .map_err(|_| parse_error(format!("expected an index, found {word}")))
+    }
+
+    /// Decimal or rational number such as `-1.5` or `1/3`
+    fn number(&mut self) -> Result<f64, MontyError> {
+        let word = self.word()?;
+        let error = || parse_error(format!("expected a number, found {word}"));
+
+        match word.split_once('/') {
+            None => word.parse().map_err(|_| error()),
+            Some((p, q)) => {
+                let p: f64 = p.parse().map_err(|_| error())?;
+                let q: f64 = q.parse().map_err(|_| error())?;
+                Ok(p / q)
+            }
+        }
+    }
+
+    /// Strings enclosed in braces
+    fn texts(&mut self) -> Result<Vec<String>, MontyError> {
+        self.expect(Token::Open)?;
+
+        let mut ans = vec![];
+        while !self.accept(&Token::Close) {
+            ans.push(self.text()?);
+        }
+
+        Ok(ans)
+    }
+
+    /// Numbers enclosed in braces
+    fn numbers(&mut self) -> Result<Vec<f64>, MontyError> {
+        self.expect(Token::Open)?;
+
+        let mut ans = vec![];
+        while !self.accept(&Token::Close) {
+            ans.push(self.number()?);
+        }
+
+        Ok(ans)
+    }
+
+    /// Checks the header `<format> <version> <precision> "title" { "player" ... }` and returns
+    /// the title and the names of the players
+    fn header(&mut self, format: &str, version: &str) -> Result<(String, Vec<String>), MontyError> {
+        let found = self.word()?;
+        if found != format {
+            return Err(parse_error(format!("expected {format}, found {found}")));
+        }
+
+        let found = self.word()?;
+        if found != version {
+            return Err(parse_error(format!("unsupported version {found}")));
+        }
+
+        let precision = self.word()?;
+        if precision != "R" && precision != "D" {
+            return Err(parse_error(format!("unknown precision {precision}")));
+        }
+
+        let title = self.text()?;
+        let players = self.texts()?;
+
+        if players.len() != 2 {
+            return Err(parse_error(format!(
+                "only two-player games are supported, found {} players",
+                players.len()
+            )));
+        }
+
+        Ok((title, players))
+    }
+}
@@ -0,0 +1,336 @@
+use super::{parse_error, Token, Tokens};
+use crate::error::MontyError;
+use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+use float_cmp::approx_eq;
+use std::collections::HashMap;
+
+/// Action of every reachable information set of one of the players
+type PureStrategy = HashMap<usize, usize>;
+
+#[derive(Debug, Clone, PartialEq)]
+struct InfoSet {
+    /// Zero for the chance, one or two for the players
+    player: usize,
+    name: String,
+    actions: Vec<String>,
+    /// Probabilities of the actions of the chance
+    probabilities: Vec<f64>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Node {
+    Inner {
+        infoset: usize,
+        children: Vec<usize>,
+    },
+    Terminal([f64; 2]),
+}
+
+/// Two-player game in the extensive form as read from a Gambit `.efg` file
+///
+/// Gambit allows information sets that cannot be told apart by observation histories, so the
+/// game is not turned into a `GameTree`; it is solved through its normal form instead
+#[derive(Debug, Clone, PartialEq)]
+pub struct EfgGame {
+    title: String,
+    infosets: Vec<InfoSet>,
+    nodes: Vec<Node>,
+}
+
+struct Parser {
+    tokens: Tokens,
+    infosets: Vec<InfoSet>,
+    /// Information sets by the player and the number in the file
+    numbers: HashMap<(usize, usize), usize>,
+    outcomes: HashMap<usize, [f64; 2]>,
+    nodes: Vec<Node>,
+}
+
+impl Parser {
+    /// Reads `number ["name" { actions }]`, registering the information set on its first use
+    fn infoset(&mut self, player: usize) -> Result<usize, MontyError> {
+        let number = self.tokens.index()?;
+        let name = self.tokens.optional_text();
+
+        let mut actions = vec![];
+        let mut probabilities = vec![];
+        let listed = self.tokens.accept(&Token::Open);
+        if listed {
+            while !self.tokens.accept(&Token::Close) {
+                actions.push(self.tokens.text()?);
+                if player == 0 {
+                    probabilities.push(self.tokens.number()?);
+                }
+            }
+        }
+
+        let total: f64 = probabilities.iter().sum();
+        if listed
+            && player == 0
+            && (!approx_eq!(f64, total, 1., epsilon = 1e-9)
+                || probabilities.iter().any(|p| *p < 0.))
+        {
+            return Err(MontyError::BadProbabilities(total));
+        }
+
+        match self.numbers.get(&(player, number)) {
+            Some(index) => Ok(*index),
+            None if listed => {
+                let index = self.infosets.len();
+                self.infosets.push(InfoSet {
+                    player,
+                    name: name.unwrap_or_default(),
+                    actions,
+                    probabilities,
+                });
+                self.numbers.insert((player, number), index);
+                Ok(index)
+            }
+            None => Err(parse_error(format!(
+                "actions of information set {number} of player {player} are not listed"
+            ))),
+        }
+    }
+
+    /// Reads `number ["name" { payoffs }]` and returns the payoffs of the outcome
+    fn outcome(&mut self) -> Result<[f64; 2], MontyError> {
+        let number = self.tokens.index()?;
+        self.tokens.optional_text();
+
+        if self.tokens.peek() == Some(&Token::Open) {
+            let payoffs = self.tokens.numbers()?;
+            let payoffs: [f64; 2] = payoffs
+                .try_into()
+                .map_err(|_| parse_error(format!("outcome {number} needs two payoffs")))?;
+            self.outcomes.insert(number, payoffs);
+        }
+
+        match number {
+            0 => Ok([0.; 2]),
+            _ => {
+                self.outcomes.get(&number).copied().ok_or_else(|| {
+                    parse_error(format!("payoffs of outcome {number} are not listed"))
+                })
+            }
+        }
+    }
+
+    /// Reads the subtree in preorder, adding up the outcomes along the way
+    fn node(&mut self, payoffs: [f64; 2]) -> Result<usize, MontyError> {
+        let index = self.nodes.len();
+        self.nodes.push(Node::Terminal(payoffs));
+
+        let kind = self.tokens.word()?;
+        self.tokens.text()?;
+
+        let player = match kind.as_str() {
+            "c" => 0,
+            "p" => self.tokens.index()?,
+            "t" => {
+                let [a, b] = self.outcome()?;
+                self.nodes[index] = Node::Terminal([payoffs[0] + a, payoffs[1] + b]);
+                return Ok(index);
+            }
+            _ => return Err(parse_error(format!("unknown node type {kind}"))),
+        };
+
+        if player > 2 {
+            return Err(parse_error(format!("no player {player}")));
+        }
+
+        let infoset = self.infoset(player)?;
+        let [a, b] = self.outcome()?;
+        let payoffs = [payoffs[0] + a, payoffs[1] + b];
+        let children = (0..self.infosets[infoset].actions.len())
+            .map(|_| self.node(payoffs))
+            .collect::<Result<_, _>>()?;
+
+        self.nodes[index] = Node::Inner { infoset, children };
+
+        Ok(index)
+    }
+}
+
+impl EfgGame {
+    /// Reads a two-player game in the Gambit `.efg` format
+    pub fn from_efg(input: &str) -> Result<Self, MontyError> {
+        let mut tokens = Tokens::new(input)?;
+        let (title, _) = tokens.header("EFG", "2")?;
+        tokens.optional_text();
+
+        let mut parser = Parser {
+            tokens,
+            infosets: vec![],
+            numbers: HashMap::new(),
+            outcomes: HashMap::new(),
+            nodes: vec![],
+        };
+        parser.node([0.; 2])?;
+
+        if !parser.tokens.is_done() {
+            return Err(parse_error("unexpected data after the tree"));
+        }
+
+        Ok(Self {
+            title,
+            infosets: parser.infosets,
+            nodes: parser.nodes,
+        })
+    }
+
+    pub fn title(&self) -> &str {
+        &self.title
+    }
+
+    fn value(&self, strategies: [&PureStrategy; 2], v: usize) -> [f64; 2] {
+        match &self.nodes[v] {
+            Node::Terminal(payoffs) => *payoffs,
+            Node::Inner { infoset, children } => {
+                let info = &self.infosets[*infoset];
+
+                if info.player == 0 {
+                    children
+                        .iter()
+                        .zip(&info.probabilities)
+                        .fold([0.; 2], |[a, b], (u, p)| {
+                            let [x, y] = self.value(strategies, *u);
+                            [a + x * p, b + y * p]
+                        })
+                } else {
+                    self.value(strategies, children[strategies[info.player - 1][infoset]])
+                }
+            }
+        }
+    }
+
+    /// Reachable information set of `player` that `strategy` does not choose an action for
+    fn unassigned(&self, player: usize, strategy: &PureStrategy, v: usize) -> Option<usize> {
+        match &self.nodes[v] {
+            Node::Terminal(_) => None,
+            Node::Inner { infoset, children } if self.infosets[*infoset].player == player => {
+                match strategy.get(infoset) {
+                    None => Some(*infoset),
+                    Some(a) => self.unassigned(player, strategy, children[*a]),
+                }
+            }
+            Node::Inner { children, .. } => children
+                .iter()
+                .find_map(|u| self.unassigned(player, strategy, *u)),
+        }
+    }
+
+    /// Pure strategies of `player` (one or two) choosing actions only at the reachable
+    /// information sets
+    fn strategies(&self, player: usize) -> Vec<PureStrategy> {
+        let mut ans = Vec::new();
+        let mut stack = vec![PureStrategy::new()];
+
+        while let Some(s) = stack.pop() {
+            match self.unassigned(player, &s, 0) {
+                None => ans.push(s),
+                Some(infoset) => {
+                    for a in 0..self.infosets[infoset].actions.len() {
+                        let mut q = s.clone();
+                        q.insert(infoset, a);
+                        stack.push(q);
+                    }
+                }
+            }
+        }
+
+        ans
+    }
+
+    fn describe(&self, strategy: &PureStrategy) -> String {
+        let mut turns: Vec<_> = strategy.iter().collect();
+        turns.sort();
+
+        if turns.is_empty() {
+            return String::from("(no moves)");
+        }
+
+        turns
+            .into_iter()
+            .map(|(infoset, a)| {
+                let info = &self.infosets[*infoset];
+                if info.name.is_empty() {
+                    format!("#{infoset} -> {}", info.actions[*a])
+                } else {
+                    format!("{} -> {}", info.name, info.actions[*a])
+                }
+            })
+            .collect::<Vec<_>>()
+            .join("; ")
+    }
+
+    /// Normal form of the game with rows and columns labelled by the pure strategies
+    pub fn to_bimatrix(&self) -> BimatrixGame {
+        self.try_to_bimatrix().unwrap()
+    }
+
+    pub fn try_to_bimatrix(&self) -> Result<BimatrixGame, MontyError> {
+        let first = self.strategies(1);
+        let second = self.strategies(2);
+        let rows: Vec<String> = first.iter().map(|s| self.describe(s)).collect();
+        let cols: Vec<String> = second.iter().map(|s| self.describe(s)).collect();
+        let player = |k: usize| -> Result<PayoffMatrix, MontyError> {
+            PayoffMatrix::new(
+                first
+                    .iter()
+                    .map(|fs| second.iter().map(|ss| self.value([fs, ss], 0)[k]).collect())
+                    .collect(),
+            )?
+            .with_labels(rows.clone(), cols.clone())
+        };
+
+        BimatrixGame::new(player(0)?, player(1)?)
+    }
+
+    /// Normal form of the game with the payoffs of the first player, for zero-sum games
+    pub fn to_matrix(&self) -> PayoffMatrix {
+        self.to_bimatrix().first
+    }
+
+    pub fn try_to_matrix(&self) -> Result<PayoffMatrix, MontyError> {
+        Ok(self.try_to_bimatrix()?.first)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::GameTree;
+    use crate::games::{Host, MontyHall, Placement};
+    use crate::matrix_game::solve_zero_sum;
+
+    #[test]
+    fn reads_exported_trees() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Evil, Placement::Host));
+        let game = EfgGame::from_efg(&tree.to_efg()).unwrap();
+        let expected = solve_zero_sum(&tree.to_matrix()).value;
+
+        assert!((solve_zero_sum(&game.try_to_matrix().unwrap()).value - expected).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_bad_chance_probabilities() {
+        let efg = |p: &str| {
+            format!(
+                "EFG 2 R \"\" {{ \"A\" \"B\" }}\n\
+                 c \"\" 1 \"\" {{ \"x\" {p} \"y\" 0.5 }} 0\n\
+                 t \"\" 1 \"\" {{ 1 -1 }}\n\
+                 t \"\" 2 \"\" {{ -1 1 }}\n"
+            )
+        };
+
+        assert!(EfgGame::from_efg(&efg("0.5")).is_ok());
+        assert_eq!(
+            EfgGame::from_efg(&efg("0.75")),
+            Err(MontyError::BadProbabilities(1.25))
+        );
+        assert!(matches!(
+            EfgGame::from_efg(&efg("-0.5")),
+            Err(MontyError::BadProbabilities(_))
+        ));
+    }
+}
@@ -0,0 +1,113 @@
+use super::{parse_error, quote, Token, Tokens};
+use crate::error::MontyError;
+use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+
+fn labels(labels: &[String]) -> String {
+    labels
+        .iter()
+        .map(|s| quote(s))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+impl BimatrixGame {
+    /// Game in the Gambit `.nfg` format with the payoffs listed for every profile of strategies
+    pub fn to_nfg(&self) -> String {
+        let mut ans = format!(
+            "NFG 1 R \"\" {{ \"Player 1\" \"Player 2\" }} {{ {{ {} }} {{ {} }} }}\n\n",
+            labels(self.first.row_labels()),
+            labels(self.first.col_labels())
+        );
+
+        // The strategy of the first player changes the fastest
+        for j in 0..self.first.n_cols() {
+            for i in 0..self.first.n_rows() {
+                ans += &format!("{} {} ", self.first[i][j], self.second[i][j]);
+            }
+        }
+        ans.push('\n');
+
+        ans
+    }
+
+    /// Reads a two-player game in the Gambit `.nfg` format, either with the payoffs listed for
+    /// every profile or with a list of outcomes
+    pub fn from_nfg(input: &str) -> Result<Self, MontyError> {
+        let mut tokens = Tokens::new(input)?;
+        tokens.header("NFG", "1")?;
+
+        tokens.expect(Token::Open)?;
+        let [rows, cols]: [Vec<String>; 2] = if tokens.peek() == Some(&Token::Open) {
+            [tokens.texts()?, tokens.texts()?]
+        } else {
+            [tokens.index()?, tokens.index()?].map(|n| (1..=n).map(|i| i.to_string()).collect())
+        };
+        tokens.expect(Token::Close)?;
+        tokens.optional_text();
+
+        let (m, n) = (rows.len(), cols.len());
+        let mut payoffs: Vec<[f64; 2]> = Vec::with_capacity(m * n);
+
+        if tokens.accept(&Token::Open) {
+            let mut outcomes = vec![[0.; 2]];
+            while !tokens.accept(&Token::Close) {
+                tokens.expect(Token::Open)?;
+                tokens.text()?;
+                let outcome = [tokens.number()?, tokens.number()?];
+                tokens.expect(Token::Close)?;
+                outcomes.push(outcome);
+            }
+
+            for _ in 0..m * n {
+                let k = tokens.index()?;
+                payoffs.push(
+                    *outcomes
+                        .get(k)
+                        .ok_or_else(|| parse_error(format!("no outcome {k}")))?,
+                );
+            }
+        } else {
+            for _ in 0..m * n {
+                payoffs.push([tokens.number()?, tokens.number()?]);
+            }
+        }
+
+        if !tokens.is_done() {
+            return Err(parse_error("unexpected data after the payoffs"));
+        }
+
+        let player = |k: usize| -> Result<PayoffMatrix, MontyError> {
+            PayoffMatrix::new(
+                (0..m)
+                    .map(|i| (0..n).map(|j| payoffs[j * m + i][k]).collect())
+                    .collect(),
+            )?
+            .with_labels(rows.clone(), cols.clone())
+        };
+
+        BimatrixGame::new(player(0)?, player(1)?)
+    }
+}
+
+impl PayoffMatrix {
+    /// Zero-sum game in the Gambit `.nfg` format
+    pub fn to_nfg(&self) -> String {
+        BimatrixGame::zero_sum(self.clone()).to_nfg()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let game = BimatrixGame::new(
+            PayoffMatrix::new(vec![vec![3., 0.], vec![0., 2.]]).unwrap(),
+            PayoffMatrix::new(vec![vec![2., 0.], vec![0., 3.]]).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(BimatrixGame::from_nfg(&game.to_nfg()).unwrap(), game);
+    }
+}
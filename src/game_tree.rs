@@ -1,38 +1,100 @@
+use self::builder::{Expand, Node, NodeView, Position, Tree, Turn};
 use self::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
-use crate::game_tree::strategy::{FirstStrategy, NaiveStrategy, SecondStrategy};
-use float_cmp::assert_approx_eq;
+use crate::error::MontyError;
+use crate::game_tree::strategy::{describe_with, FirstStrategy, NaiveStrategy, SecondStrategy};
+use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+use float_cmp::approx_eq;
 use rules::{GameRules, Move, Observation, State};
 use std::hash::Hash;
 use std::marker::PhantomData;
 
+mod behavioral;
+mod best_response;
+mod builder;
+pub mod cfr;
+mod dot;
+pub mod dyn_game;
+mod efg;
+pub mod interactive;
+mod json;
+pub mod monte_carlo;
+pub mod n_player;
+pub mod reduced;
 pub mod rules;
+pub mod sequence_form;
 pub mod strategy;
 
 #[derive(Debug, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 enum GameTreeNode<M: Move> {
     RandomEvent(Vec<(M, usize, f64)>),
     FirstMoves(Vec<(M, usize)>),
     SecondMoves(Vec<(M, usize)>),
-    GameOver(f64),
+    GameOver([f64; 2]),
 }
 
-impl<M: Move> GameTreeNode<M> {
-    fn borrow_random_event_mut(&mut self) -> &mut Vec<(M, usize, f64)> {
-        match self {
-            RandomEvent(w) => w,
-            _ => panic!(),
+impl<M: Move> From<Node<M>> for GameTreeNode<M> {
+    fn from(value: Node<M>) -> Self {
+        match value {
+            Node::RandomEvent(row) => RandomEvent(row),
+            Node::PlayerMoves(0, row) => FirstMoves(row),
+            Node::PlayerMoves(_, row) => SecondMoves(row),
+            Node::GameOver(payoffs) => GameOver([payoffs[0], payoffs[1]]),
         }
     }
+}
+
+impl<M: Move> Tree<M> for [GameTreeNode<M>] {
+    fn view(&self, v: usize) -> NodeView<'_, M> {
+        match &self[v] {
+            RandomEvent(row) => NodeView::RandomEvent(row),
+            FirstMoves(row) => NodeView::PlayerMoves(0, row),
+            SecondMoves(row) => NodeView::PlayerMoves(1, row),
+            GameOver(payoffs) => NodeView::GameOver(payoffs),
+        }
+    }
+}
+
+/// Checks that the nodes form a tree as built from rules: the children of a node come after it,
+/// only the leaves have no moves and the probabilities of chance moves sum up to one
+#[cfg(feature = "serde")]
+fn check_nodes<M: Move>(nodes: &[GameTreeNode<M>]) -> Result<(), MontyError> {
+    if nodes.is_empty() {
+        return Err(MontyError::Parse(String::from("a game tree needs a root")));
+    }
+
+    for (v, node) in nodes.iter().enumerate() {
+        let children: Vec<usize> = match node {
+            RandomEvent(row) => row.iter().map(|(_, u, _)| *u).collect(),
+            FirstMoves(row) | SecondMoves(row) => row.iter().map(|(_, u)| *u).collect(),
+            GameOver(_) => continue,
+        };
+
+        if children.is_empty() {
+            return Err(MontyError::NoMoves);
+        }
+        if let RandomEvent(row) = node {
+            let total = row.iter().map(|(_, _, prob)| prob).sum();
 
-    fn borrow_player_moves_mut(&mut self) -> &mut Vec<(M, usize)> {
-        match self {
-            FirstMoves(w) | SecondMoves(w) => w,
-            _ => panic!(),
+            if !approx_eq!(f64, total, 1., epsilon = 1e-9)
+                || row.iter().any(|(_, _, prob)| *prob < 0.)
+            {
+                return Err(MontyError::BadProbabilities(total));
+            }
+        }
+        if let Some(u) = children.iter().find(|u| **u <= v || **u >= nodes.len()) {
+            return Err(MontyError::Parse(format!(
+                "node {v} cannot have node {u} as a child"
+            )));
         }
     }
+
+    Ok(())
 }
 
 #[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawGameTree<M, F, S, R>"))]
 pub struct GameTree<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> {
     rules: R,
     nodes: Vec<GameTreeNode<M>>,
@@ -40,6 +102,34 @@ pub struct GameTree<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<
     _phantom_s: PhantomData<S>,
 }
 
+/// Fields of a deserialized `GameTree`, whose nodes are checked by `check_nodes`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawGameTree<M: Move, F, S, R> {
+    rules: R,
+    nodes: Vec<GameTreeNode<M>>,
+    _phantom_f: PhantomData<F>,
+    _phantom_s: PhantomData<S>,
+}
+
+#[cfg(feature = "serde")]
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>>
+    TryFrom<RawGameTree<M, F, S, R>> for GameTree<M, F, S, R>
+{
+    type Error = MontyError;
+
+    fn try_from(value: RawGameTree<M, F, S, R>) -> Result<Self, Self::Error> {
+        check_nodes(&value.nodes)?;
+
+        Ok(Self {
+            rules: value.rules,
+            nodes: value.nodes,
+            _phantom_f: PhantomData,
+            _phantom_s: PhantomData,
+        })
+    }
+}
+
 #[derive(Debug, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
 struct Play<M: Move, F: Observation<M>, S: Observation<M>> {
     moves: Vec<M>,
@@ -97,296 +187,223 @@ impl<M: Move, F: Observation<M>, S: Observation<M>> Play<M, F, S> {
     }
 }
 
-impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
-    fn build(nodes: &mut Vec<GameTreeNode<M>>, path: &mut Play<M, F, S>, rules: &R) {
-        let index = nodes.len();
-
-        match rules.ask_arbiter(path.to_arbiter()) {
-            State::RandomEvent => {
-                let events = rules.random_event(path.to_arbiter());
-                let n = events.len();
-
-                assert_approx_eq!(f64, events.iter().map(|(_, prob)| prob).sum(), 1.);
-
-                nodes.push(RandomEvent(
-                    events
-                        .into_iter()
-                        .map(|(x, y)| (x, usize::MAX, y))
-                        .collect(),
-                ));
-
-                for j in 0..n {
-                    let u = nodes.len();
-                    let event = &mut nodes[index].borrow_random_event_mut()[j];
-                    event.1 = u;
-                    path.push_move(event.0);
-                    Self::build(nodes, path, rules);
-                    path.pop_move();
-                }
-            }
-            State::FirstToMove => {
-                let events = rules.ask_first(path.to_first());
-                let n = events.len();
-
-                nodes.push(FirstMoves(
-                    events.into_iter().map(|x| (x, usize::MAX)).collect(),
-                ));
-
-                for j in 0..n {
-                    let u = nodes.len();
-                    let event = &mut nodes[index].borrow_player_moves_mut()[j];
-                    event.1 = u;
-                    path.push_move(event.0);
-                    Self::build(nodes, path, rules);
-                    path.pop_move();
-                }
-            }
-            State::SecondToMove => {
-                let events = rules.ask_second(path.to_second());
-                let n = events.len();
-
-                nodes.push(SecondMoves(
-                    events.into_iter().map(|x| (x, usize::MAX)).collect(),
-                ));
-
-                for j in 0..n {
-                    let u = nodes.len();
-                    let event = &mut nodes[index].borrow_player_moves_mut()[j];
-                    event.1 = u;
-                    path.push_move(event.0);
-                    Self::build(nodes, path, rules);
-                    path.pop_move();
-                }
-            }
-            State::GameOver(value) => nodes.push(GameOver(value)),
-        }
+impl<M: Move, F: Observation<M>, S: Observation<M>> Position<M> for Play<M, F, S> {
+    fn push_move(&mut self, m: M) {
+        Play::push_move(self, m);
     }
 
-    pub fn from_rules(rules: R) -> Self {
-        let mut nodes = vec![];
-        let mut path = Play::new();
+    fn pop_move(&mut self) {
+        Play::pop_move(self);
+    }
+}
 
-        Self::build(&mut nodes, &mut path, &rules);
+/// Play from the root together with the rules that tell what follows it
+struct Expansion<'a, M: Move, F: Observation<M>, S: Observation<M>, R> {
+    rules: &'a R,
+    play: Play<M, F, S>,
+}
 
-        assert!(path.is_empty());
+impl<M: Move, F: Observation<M>, S: Observation<M>, R> Position<M> for Expansion<'_, M, F, S, R> {
+    fn push_move(&mut self, m: M) {
+        self.play.push_move(m);
+    }
 
-        Self {
-            nodes,
+    fn pop_move(&mut self) {
+        self.play.pop_move();
+    }
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> Expand<M>
+    for Expansion<'_, M, F, S, R>
+{
+    fn turn(&self) -> Result<Turn<M>, MontyError> {
+        let play = &self.play;
+
+        Ok(match self.rules.ask_arbiter(play.to_arbiter()) {
+            State::RandomEvent => Turn::RandomEvent(self.rules.random_event(play.to_arbiter())),
+            State::FirstToMove => Turn::ToMove(0, self.rules.ask_first(play.to_first())),
+            State::SecondToMove => Turn::ToMove(1, self.rules.ask_second(play.to_second())),
+            State::GameOver(payoffs) => Turn::GameOver(Vec::from(payoffs)),
+        })
+    }
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    pub fn from_rules(rules: R) -> Self {
+        Self::try_from_rules(rules).unwrap()
+    }
+
+    pub fn try_from_rules(rules: R) -> Result<Self, MontyError> {
+        let nodes = builder::build(&mut Expansion {
+            rules: &rules,
+            play: Play::new(),
+        })?;
+
+        Ok(Self {
+            nodes: nodes.into_iter().map(GameTreeNode::from).collect(),
             rules,
             _phantom_f: PhantomData,
             _phantom_s: PhantomData,
-        }
+        })
+    }
+
+    /// Expected payoff of the first player
+    pub fn simulate(&self, f: &impl FirstStrategy<M, F>, s: &impl SecondStrategy<M, S>) -> f64 {
+        self.try_simulate(f, s).unwrap()
     }
 
-    fn dfs(
+    pub fn try_simulate(
         &self,
         f: &impl FirstStrategy<M, F>,
         s: &impl SecondStrategy<M, S>,
-        path: &mut Play<M, F, S>,
-        v: usize,
-    ) -> f64 {
-        match &self.nodes[v] {
-            RandomEvent(row) => {
-                let mut sum = 0.;
-
-                for (m, u, p) in row {
-                    path.push_move(*m);
-                    sum += self.dfs(f, s, path, *u) * p;
-                    path.pop_move();
-                }
-
-                sum
-            }
-            FirstMoves(row) => {
-                let m = f.make_move::<S>(path.to_first(), &self.rules);
+    ) -> Result<f64, MontyError> {
+        Ok(self.try_simulate_payoffs(f, s)?[0])
+    }
 
-                let u = row
-                    .iter()
-                    .copied()
-                    .find_map(|(a, b)| if a == m { Some(b) } else { None })
-                    .unwrap();
-
-                path.push_move(m);
-                let ans = self.dfs(f, s, path, u);
-                path.pop_move();
-                ans
-            }
-            SecondMoves(row) => {
-                let m = s.make_move::<F>(path.to_second(), &self.rules);
+    /// Expected payoffs of both players
+    pub fn simulate_payoffs(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+    ) -> [f64; 2] {
+        self.try_simulate_payoffs(f, s).unwrap()
+    }
 
-                let u = row
-                    .iter()
-                    .copied()
-                    .find_map(|(a, b)| if a == m { Some(b) } else { None })
-                    .unwrap();
-
-                path.push_move(m);
-                let ans = self.dfs(f, s, path, u);
-                path.pop_move();
-                ans
+    pub fn try_simulate_payoffs(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+    ) -> Result<[f64; 2], MontyError> {
+        let payoffs = builder::simulate(&self.nodes[..], &mut Play::new(), 0, &|player, play| {
+            if player == 0 {
+                f.make_move::<S>(play.to_first(), &self.rules)
+            } else {
+                s.make_move::<F>(play.to_second(), &self.rules)
             }
-            GameOver(x) => *x,
-        }
+        })?;
+
+        Ok([payoffs[0], payoffs[1]])
     }
 
-    pub fn simulate(&self, f: &impl FirstStrategy<M, F>, s: &impl SecondStrategy<M, S>) -> f64 {
-        self.dfs(f, s, &mut Play::new(), 0)
+    /// Whether the payoffs of the players sum up to zero in every outcome, which the zero-sum
+    /// solvers assume: they only look at the first player's payoffs
+    pub fn is_zero_sum(&self) -> bool {
+        self.nodes.iter().all(|node| match node {
+            GameOver([a, b]) => approx_eq!(f64, a + b, 0.),
+            _ => true,
+        })
     }
 
     pub fn strategy_matrix(
         &self,
         f: &[impl FirstStrategy<M, F>],
         s: &[impl SecondStrategy<M, S>],
-    ) -> Vec<Vec<f64>> {
-        f.iter()
-            .map(|fs| s.iter().map(|ss| self.simulate(fs, ss)).collect())
-            .collect()
+    ) -> PayoffMatrix {
+        self.try_strategy_matrix(f, s).unwrap()
     }
 
-    pub fn to_matrix(&self) -> Vec<Vec<f64>> {
-        self.strategy_matrix(
-            &self.list_all_first_strategies(),
-            &self.list_all_second_strategies(),
+    pub fn try_strategy_matrix(
+        &self,
+        f: &[impl FirstStrategy<M, F>],
+        s: &[impl SecondStrategy<M, S>],
+    ) -> Result<PayoffMatrix, MontyError> {
+        PayoffMatrix::new(
+            f.iter()
+                .map(|fs| s.iter().map(|ss| self.try_simulate(fs, ss)).collect())
+                .collect::<Result<_, _>>()?,
         )
     }
 
-    fn check_first_strategy(
-        &self,
-        play: &mut Play<M, F, S>,
-        strategy: &NaiveStrategy<M, F>,
-        v: usize,
-    ) -> Option<Vec<NaiveStrategy<M, F>>> {
-        match &self.nodes[v] {
-            FirstMoves(row) => {
-                let turn = strategy.get(play.to_first());
-
-                match turn {
-                    None => Some(
-                        row.iter()
-                            .map(|(m, _)| {
-                                let mut q = strategy.clone();
-                                q.insert(Vec::from(play.to_first()), *m);
-                                q
-                            })
-                            .collect(),
-                    ),
-                    Some(m) => {
-                        play.push_move(*m);
-                        let ans = self.check_first_strategy(
-                            play,
-                            strategy,
-                            row.iter().find(|(rm, _)| rm == m).unwrap().1,
-                        );
-                        play.pop_move();
-                        ans
-                    }
-                }
-            }
-            RandomEvent(row) => row
-                .iter()
-                .map(|(m, u, _)| {
-                    play.push_move(*m);
-                    let ans = self.check_first_strategy(play, strategy, *u);
-                    play.pop_move();
-                    ans
-                })
-                .find(|w| w.is_some())
-                .flatten(),
-            SecondMoves(row) => row
-                .iter()
-                .map(|(m, u)| {
-                    play.push_move(*m);
-                    let ans = self.check_first_strategy(play, strategy, *u);
-                    play.pop_move();
-                    ans
-                })
-                .find(|w| w.is_some())
-                .flatten(),
-            GameOver(_) => None,
-        }
+    /// Normal form of a general-sum game with rows and columns labelled by the pure strategies
+    pub fn to_bimatrix(&self) -> BimatrixGame {
+        self.try_to_bimatrix().unwrap()
     }
 
-    fn check_second_strategy(
-        &self,
-        play: &mut Play<M, F, S>,
-        strategy: &NaiveStrategy<M, S>,
-        v: usize,
-    ) -> Option<Vec<NaiveStrategy<M, S>>> {
-        match &self.nodes[v] {
-            SecondMoves(row) => {
-                let turn = strategy.get(play.to_second());
-
-                match turn {
-                    None => Some(
-                        row.iter()
-                            .map(|(m, _)| {
-                                let mut q = strategy.clone();
-                                q.insert(Vec::from(play.to_second()), *m);
-                                q
-                            })
-                            .collect(),
-                    ),
-                    Some(m) => {
-                        play.push_move(*m);
-                        let ans = self.check_second_strategy(
-                            play,
-                            strategy,
-                            row.iter().find(|(rm, _)| rm == m).unwrap().1,
-                        );
-                        play.pop_move();
-                        ans
-                    }
-                }
-            }
-            RandomEvent(row) => row
-                .iter()
-                .map(|(m, u, _)| {
-                    play.push_move(*m);
-                    let ans = self.check_second_strategy(play, strategy, *u);
-                    play.pop_move();
-                    ans
-                })
-                .find(|w| w.is_some())
-                .flatten(),
-            FirstMoves(row) => row
-                .iter()
-                .map(|(m, u)| {
-                    play.push_move(*m);
-                    let ans = self.check_second_strategy(play, strategy, *u);
-                    play.pop_move();
-                    ans
-                })
-                .find(|w| w.is_some())
-                .flatten(),
-            GameOver(_) => None,
-        }
+    pub fn try_to_bimatrix(&self) -> Result<BimatrixGame, MontyError> {
+        let first = self.list_all_first_strategies();
+        let second = self.list_all_second_strategies();
+        let rows: Vec<String> = first.iter().map(|s| self.describe(s)).collect();
+        let cols: Vec<String> = second.iter().map(|s| self.describe(s)).collect();
+        let payoffs: Vec<Vec<[f64; 2]>> = first
+            .iter()
+            .map(|fs| {
+                second
+                    .iter()
+                    .map(|ss| self.try_simulate_payoffs(fs, ss))
+                    .collect()
+            })
+            .collect::<Result<_, _>>()?;
+        let player = |k: usize| -> Result<PayoffMatrix, MontyError> {
+            PayoffMatrix::new(
+                payoffs
+                    .iter()
+                    .map(|row| row.iter().map(|x| x[k]).collect())
+                    .collect(),
+            )?
+            .with_labels(rows.clone(), cols.clone())
+        };
+
+        BimatrixGame::new(player(0)?, player(1)?)
     }
 
-    pub fn list_all_first_strategies(&self) -> Vec<NaiveStrategy<M, F>> {
-        let mut ans = Vec::new();
-        let mut stack = vec![NaiveStrategy::new()];
+    /// Normal form of the game with rows and columns labelled by the pure strategies
+    pub fn to_matrix(&self) -> PayoffMatrix {
+        self.try_to_matrix().unwrap()
+    }
 
-        while let Some(s) = stack.pop() {
-            match self.check_first_strategy(&mut Play::new(), &s, 0) {
-                None => ans.push(s),
-                Some(updated) => stack.extend_from_slice(&updated),
-            }
-        }
+    pub fn try_to_matrix(&self) -> Result<PayoffMatrix, MontyError> {
+        let first = self.list_all_first_strategies();
+        let second = self.list_all_second_strategies();
 
-        ans
+        self.try_strategy_matrix(&first, &second)?.with_labels(
+            first.iter().map(|s| self.describe(s)).collect(),
+            second.iter().map(|s| self.describe(s)).collect(),
+        )
     }
 
-    pub fn list_all_second_strategies(&self) -> Vec<NaiveStrategy<M, S>> {
-        let mut ans = Vec::new();
-        let mut stack = vec![NaiveStrategy::new()];
+    /// Human-readable description of a pure strategy of either player, with the moves named by
+    /// the rules
+    pub fn describe<O: Observation<M>>(&self, strategy: &NaiveStrategy<M, O>) -> String {
+        describe_with(strategy, |m| self.rules.move_name(m))
+    }
 
-        while let Some(s) = stack.pop() {
-            match self.check_second_strategy(&mut Play::new(), &s, 0) {
-                None => ans.push(s),
-                Some(updated) => stack.extend_from_slice(&updated),
-            }
-        }
+    pub fn list_all_first_strategies(&self) -> Vec<NaiveStrategy<M, F>> {
+        builder::list_strategies(&self.nodes[..], 0, Play::new, |play: &Play<M, F, S>| {
+            Vec::from(play.to_first())
+        })
+    }
+
+    pub fn list_all_second_strategies(&self) -> Vec<NaiveStrategy<M, S>> {
+        builder::list_strategies(&self.nodes[..], 1, Play::new, |play: &Play<M, F, S>| {
+            Vec::from(play.to_second())
+        })
+    }
+}
 
-        ans
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Host, MontyHall, Placement};
+
+    #[test]
+    fn simulate_rejects_unknown_histories() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Host));
+        let first = tree.list_all_first_strategies();
+        let mut second = tree.list_all_second_strategies().swap_remove(0);
+
+        let mut empty = first[0].clone();
+        empty.clear();
+
+        assert!(tree.try_simulate(&first[0], &second).is_ok());
+        assert_eq!(
+            tree.try_simulate(&empty, &second),
+            Err(MontyError::IllegalMove)
+        );
+
+        second.remove(&vec![]);
+        assert_eq!(
+            tree.try_simulate(&first[0], &second),
+            Err(MontyError::IllegalMove)
+        );
     }
 }
@@ -0,0 +1,225 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::{BehavioralStrategy, MixedStrategy, NaiveStrategy};
+use std::hash::Hash;
+
+/// Distribution over the moves of the pure strategies in `consistent` at `history`
+fn conditional<M: Move, O: Hash + Eq>(
+    row: &[(M, usize)],
+    history: &[O],
+    mixed: &[(NaiveStrategy<M, O>, f64)],
+    consistent: &[usize],
+) -> Vec<(M, f64)> {
+    let total: f64 = consistent.iter().map(|i| mixed[*i].1).sum();
+
+    row.iter()
+        .map(|(m, _)| {
+            if total > 0. {
+                let p: f64 = consistent
+                    .iter()
+                    .filter(|i| mixed[**i].0.get(history) == Some(m))
+                    .map(|i| mixed[*i].1)
+                    .sum();
+                (*m, p / total)
+            } else {
+                (*m, 1. / row.len() as f64)
+            }
+        })
+        .collect()
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    fn kuhn_first(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        mixed: &MixedStrategy<M, F>,
+        consistent: &[usize],
+        ans: &mut BehavioralStrategy<M, F>,
+    ) {
+        match &self.nodes[v] {
+            RandomEvent(row) => {
+                for (m, u, _) in row {
+                    play.push_move(*m);
+                    self.kuhn_first(play, *u, mixed, consistent, ans);
+                    play.pop_move();
+                }
+            }
+            FirstMoves(row) => {
+                ans.entry(Vec::from(play.to_first()))
+                    .or_insert_with(|| conditional(row, play.to_first(), mixed, consistent));
+
+                for (m, u) in row {
+                    let next: Vec<usize> = consistent
+                        .iter()
+                        .copied()
+                        .filter(|i| mixed[*i].0.get(play.to_first()) == Some(m))
+                        .collect();
+                    play.push_move(*m);
+                    self.kuhn_first(play, *u, mixed, &next, ans);
+                    play.pop_move();
+                }
+            }
+            SecondMoves(row) => {
+                for (m, u) in row {
+                    play.push_move(*m);
+                    self.kuhn_first(play, *u, mixed, consistent, ans);
+                    play.pop_move();
+                }
+            }
+            GameOver(_) => {}
+        }
+    }
+
+    fn kuhn_second(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        mixed: &MixedStrategy<M, S>,
+        consistent: &[usize],
+        ans: &mut BehavioralStrategy<M, S>,
+    ) {
+        match &self.nodes[v] {
+            RandomEvent(row) => {
+                for (m, u, _) in row {
+                    play.push_move(*m);
+                    self.kuhn_second(play, *u, mixed, consistent, ans);
+                    play.pop_move();
+                }
+            }
+            FirstMoves(row) => {
+                for (m, u) in row {
+                    play.push_move(*m);
+                    self.kuhn_second(play, *u, mixed, consistent, ans);
+                    play.pop_move();
+                }
+            }
+            SecondMoves(row) => {
+                ans.entry(Vec::from(play.to_second()))
+                    .or_insert_with(|| conditional(row, play.to_second(), mixed, consistent));
+
+                for (m, u) in row {
+                    let next: Vec<usize> = consistent
+                        .iter()
+                        .copied()
+                        .filter(|i| mixed[*i].0.get(play.to_second()) == Some(m))
+                        .collect();
+                    play.push_move(*m);
+                    self.kuhn_second(play, *u, mixed, &next, ans);
+                    play.pop_move();
+                }
+            }
+            GameOver(_) => {}
+        }
+    }
+
+    /// Behavioural strategy of the first player equivalent to `mixed` (Kuhn's theorem);
+    /// histories `mixed` never reaches get uniform distributions
+    pub fn first_behavioral(&self, mixed: &MixedStrategy<M, F>) -> BehavioralStrategy<M, F> {
+        let mut ans = BehavioralStrategy::new();
+        let consistent: Vec<usize> = (0..mixed.len()).collect();
+
+        self.kuhn_first(&mut Play::new(), 0, mixed, &consistent, &mut ans);
+
+        ans
+    }
+
+    /// Behavioural strategy of the second player equivalent to `mixed` (Kuhn's theorem);
+    /// histories `mixed` never reaches get uniform distributions
+    pub fn second_behavioral(&self, mixed: &MixedStrategy<M, S>) -> BehavioralStrategy<M, S> {
+        let mut ans = BehavioralStrategy::new();
+        let consistent: Vec<usize> = (0..mixed.len()).collect();
+
+        self.kuhn_second(&mut Play::new(), 0, mixed, &consistent, &mut ans);
+
+        ans
+    }
+
+    /// Exact value of the game for the first player when both players follow behavioural
+    /// strategies
+    pub fn expected_value(
+        &self,
+        first: &BehavioralStrategy<M, F>,
+        second: &BehavioralStrategy<M, S>,
+    ) -> f64 {
+        self.try_expected_value(first, second).unwrap()
+    }
+
+    pub fn try_expected_value(
+        &self,
+        first: &BehavioralStrategy<M, F>,
+        second: &BehavioralStrategy<M, S>,
+    ) -> Result<f64, MontyError> {
+        Ok(self.try_expected_payoffs(first, second)?[0])
+    }
+
+    /// Exact expected payoffs of both players when they follow behavioural strategies
+    pub fn expected_payoffs(
+        &self,
+        first: &BehavioralStrategy<M, F>,
+        second: &BehavioralStrategy<M, S>,
+    ) -> [f64; 2] {
+        self.try_expected_payoffs(first, second).unwrap()
+    }
+
+    pub fn try_expected_payoffs(
+        &self,
+        first: &BehavioralStrategy<M, F>,
+        second: &BehavioralStrategy<M, S>,
+    ) -> Result<[f64; 2], MontyError> {
+        self.expected(
+            &mut Play::new(),
+            0,
+            &|h| first.get(h).cloned().ok_or(MontyError::IllegalMove),
+            &|h| second.get(h).cloned().ok_or(MontyError::IllegalMove),
+        )
+    }
+
+    /// Exact value of the game when both players follow mixed strategies
+    pub fn mixed_value(&self, first: &MixedStrategy<M, F>, second: &MixedStrategy<M, S>) -> f64 {
+        self.try_mixed_value(first, second).unwrap()
+    }
+
+    pub fn try_mixed_value(
+        &self,
+        first: &MixedStrategy<M, F>,
+        second: &MixedStrategy<M, S>,
+    ) -> Result<f64, MontyError> {
+        let mut sum = 0.;
+
+        for (f, p) in first {
+            for (s, q) in second {
+                sum += self.try_simulate(f, s)? * p * q;
+            }
+        }
+
+        Ok(sum)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_tree::strategy::to_mixed;
+    use crate::game_tree::GameTree;
+    use crate::games::{Host, MontyHall, Placement};
+    use crate::matrix_game::solve_zero_sum;
+
+    #[test]
+    fn behavioral_strategies_keep_the_value() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Host));
+        let first = tree.list_all_first_strategies();
+        let second = tree.list_all_second_strategies();
+        let equilibrium = solve_zero_sum(&tree.strategy_matrix(&first, &second));
+        let first = to_mixed(&first, &equilibrium.first);
+        let second = to_mixed(&second, &equilibrium.second);
+        let value = tree.expected_value(
+            &tree.first_behavioral(&first),
+            &tree.second_behavioral(&second),
+        );
+
+        assert!((tree.mixed_value(&first, &second) - equilibrium.value).abs() < 1e-6);
+        assert!((value - equilibrium.value).abs() < 1e-6);
+    }
+}
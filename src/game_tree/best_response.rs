@@ -0,0 +1,440 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::{FirstStrategy, NaiveStrategy, SecondStrategy};
+use std::collections::HashMap;
+
+/// Strategy of a player given as a distribution over moves for every observation history, failing
+/// on the histories it does not know
+pub(super) type Policy<'a, M, O> = &'a dyn Fn(&[O]) -> Result<Vec<(M, f64)>, MontyError>;
+
+/// Nodes of an information set with the probability to reach them from the root when the player
+/// owning the information set plays towards it
+type Reach<M, F, S> = Vec<(usize, Play<M, F, S>, f64)>;
+
+fn child<M: Move>(row: &[(M, usize)], m: M) -> Result<usize, MontyError> {
+    row.iter()
+        .find_map(|(a, u)| if *a == m { Some(*u) } else { None })
+        .ok_or(MontyError::IllegalMove)
+}
+
+fn probability<M: Move>(policy: &[(M, f64)], m: M) -> f64 {
+    policy
+        .iter()
+        .filter(|(a, _)| *a == m)
+        .map(|(_, p)| *p)
+        .sum()
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    /// Expected payoffs of both players in the subtree of `v` when they follow their policies
+    pub(super) fn expected(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        first: Policy<M, F>,
+        second: Policy<M, S>,
+    ) -> Result<[f64; 2], MontyError> {
+        let mut sum = [0.; 2];
+        let mut add = |x: [f64; 2], p: f64| {
+            sum[0] += x[0] * p;
+            sum[1] += x[1] * p;
+        };
+
+        match &self.nodes[v] {
+            RandomEvent(row) => {
+                for (m, u, p) in row {
+                    play.push_move(*m);
+                    add(self.expected(play, *u, first, second)?, *p);
+                    play.pop_move();
+                }
+            }
+            FirstMoves(row) => {
+                let policy = first(play.to_first())?;
+                if policy.is_empty() {
+                    return Err(MontyError::IllegalMove);
+                }
+
+                for (m, p) in policy {
+                    let u = child(row, m)?;
+                    if p != 0. {
+                        play.push_move(m);
+                        add(self.expected(play, u, first, second)?, p);
+                        play.pop_move();
+                    }
+                }
+            }
+            SecondMoves(row) => {
+                let policy = second(play.to_second())?;
+                if policy.is_empty() {
+                    return Err(MontyError::IllegalMove);
+                }
+
+                for (m, p) in policy {
+                    let u = child(row, m)?;
+                    if p != 0. {
+                        play.push_move(m);
+                        add(self.expected(play, u, first, second)?, p);
+                        play.pop_move();
+                    }
+                }
+            }
+            GameOver(x) => add(*x, 1.),
+        }
+
+        Ok(sum)
+    }
+
+    fn reach_first(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        w: f64,
+        second: Policy<M, S>,
+        infosets: &mut HashMap<Vec<F>, Reach<M, F, S>>,
+    ) -> Result<(), MontyError> {
+        match &self.nodes[v] {
+            RandomEvent(row) => {
+                for (m, u, p) in row {
+                    play.push_move(*m);
+                    self.reach_first(play, *u, w * p, second, infosets)?;
+                    play.pop_move();
+                }
+            }
+            FirstMoves(row) => {
+                infosets
+                    .entry(Vec::from(play.to_first()))
+                    .or_default()
+                    .push((v, play.clone(), w));
+
+                for (m, u) in row {
+                    play.push_move(*m);
+                    self.reach_first(play, *u, w, second, infosets)?;
+                    play.pop_move();
+                }
+            }
+            SecondMoves(row) => {
+                // Histories the opponent never reaches may be unknown to its strategy
+                let policy = if w != 0. {
+                    second(play.to_second())?
+                } else {
+                    vec![]
+                };
+                for (m, _) in &policy {
+                    child(row, *m)?;
+                }
+
+                for (m, u) in row {
+                    play.push_move(*m);
+                    self.reach_first(play, *u, w * probability(&policy, *m), second, infosets)?;
+                    play.pop_move();
+                }
+            }
+            GameOver(_) => {}
+        }
+
+        Ok(())
+    }
+
+    fn reach_second(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        w: f64,
+        first: Policy<M, F>,
+        infosets: &mut HashMap<Vec<S>, Reach<M, F, S>>,
+    ) -> Result<(), MontyError> {
+        match &self.nodes[v] {
+            RandomEvent(row) => {
+                for (m, u, p) in row {
+                    play.push_move(*m);
+                    self.reach_second(play, *u, w * p, first, infosets)?;
+                    play.pop_move();
+                }
+            }
+            FirstMoves(row) => {
+                // Histories the opponent never reaches may be unknown to its strategy
+                let policy = if w != 0. {
+                    first(play.to_first())?
+                } else {
+                    vec![]
+                };
+                for (m, _) in &policy {
+                    child(row, *m)?;
+                }
+
+                for (m, u) in row {
+                    play.push_move(*m);
+                    self.reach_second(play, *u, w * probability(&policy, *m), first, infosets)?;
+                    play.pop_move();
+                }
+            }
+            SecondMoves(row) => {
+                infosets
+                    .entry(Vec::from(play.to_second()))
+                    .or_default()
+                    .push((v, play.clone(), w));
+
+                for (m, u) in row {
+                    play.push_move(*m);
+                    self.reach_second(play, *u, w, first, infosets)?;
+                    play.pop_move();
+                }
+            }
+            GameOver(_) => {}
+        }
+
+        Ok(())
+    }
+
+    /// Pure strategy of the first player maximizing their payoff against `second`, and the
+    /// payoffs of both players
+    pub(super) fn first_best_response(
+        &self,
+        second: Policy<M, S>,
+    ) -> Result<(NaiveStrategy<M, F>, [f64; 2]), MontyError> {
+        let mut infosets = HashMap::new();
+        self.reach_first(&mut Play::new(), 0, 1., second, &mut infosets)?;
+
+        // Information sets deeper in the tree have longer observation histories
+        let mut order: Vec<_> = infosets.into_iter().collect();
+        order.sort_by_key(|(history, _)| std::cmp::Reverse(history.len()));
+
+        let mut chosen = NaiveStrategy::new();
+
+        for (history, nodes) in order {
+            let FirstMoves(row) = &self.nodes[nodes[0].0] else {
+                unreachable!()
+            };
+            let mut best = None;
+
+            for (m, _) in row {
+                let mut value = 0.;
+
+                for (v, play, w) in nodes.iter().filter(|(_, _, w)| *w != 0.) {
+                    let FirstMoves(row) = &self.nodes[*v] else {
+                        unreachable!()
+                    };
+                    let mut play = play.clone();
+                    play.push_move(*m);
+                    value += w * self.expected(
+                        &mut play,
+                        child(row, *m)?,
+                        &|h| Ok(vec![(chosen[h], 1.)]),
+                        second,
+                    )?[0];
+                }
+
+                if best.is_none_or(|(_, b)| value > b) {
+                    best = Some((*m, value));
+                }
+            }
+
+            chosen.insert(history, best.unwrap().0);
+        }
+
+        let payoffs = self.expected(&mut Play::new(), 0, &|h| Ok(vec![(chosen[h], 1.)]), second)?;
+
+        Ok((chosen, payoffs))
+    }
+
+    /// Pure strategy of the second player maximizing their payoff against `first`, and the
+    /// payoffs of both players
+    pub(super) fn second_best_response(
+        &self,
+        first: Policy<M, F>,
+    ) -> Result<(NaiveStrategy<M, S>, [f64; 2]), MontyError> {
+        let mut infosets = HashMap::new();
+        self.reach_second(&mut Play::new(), 0, 1., first, &mut infosets)?;
+
+        // Information sets deeper in the tree have longer observation histories
+        let mut order: Vec<_> = infosets.into_iter().collect();
+        order.sort_by_key(|(history, _)| std::cmp::Reverse(history.len()));
+
+        let mut chosen = NaiveStrategy::new();
+
+        for (history, nodes) in order {
+            let SecondMoves(row) = &self.nodes[nodes[0].0] else {
+                unreachable!()
+            };
+            let mut best = None;
+
+            for (m, _) in row {
+                let mut value = 0.;
+
+                for (v, play, w) in nodes.iter().filter(|(_, _, w)| *w != 0.) {
+                    let SecondMoves(row) = &self.nodes[*v] else {
+                        unreachable!()
+                    };
+                    let mut play = play.clone();
+                    play.push_move(*m);
+                    value += w * self.expected(&mut play, child(row, *m)?, first, &|h| {
+                        Ok(vec![(chosen[h], 1.)])
+                    })?[1];
+                }
+
+                if best.is_none_or(|(_, b)| value > b) {
+                    best = Some((*m, value));
+                }
+            }
+
+            chosen.insert(history, best.unwrap().0);
+        }
+
+        let payoffs = self.expected(&mut Play::new(), 0, first, &|h| Ok(vec![(chosen[h], 1.)]))?;
+
+        Ok((chosen, payoffs))
+    }
+
+    /// Sum of the gains of both players from deviating from the given policies
+    pub(super) fn policy_exploitability(
+        &self,
+        first: Policy<M, F>,
+        second: Policy<M, S>,
+    ) -> Result<f64, MontyError> {
+        let current = self.expected(&mut Play::new(), 0, first, second)?;
+        let (_, best_first) = self.first_best_response(second)?;
+        let (_, best_second) = self.second_best_response(first)?;
+
+        Ok(best_first[0] - current[0] + best_second[1] - current[1])
+    }
+
+    /// Optimal pure strategy of the second player against `f`, and the resulting payoff of the
+    /// first player
+    pub fn best_response_to_first(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+    ) -> (NaiveStrategy<M, S>, f64) {
+        self.try_best_response_to_first(f).unwrap()
+    }
+
+    pub fn try_best_response_to_first(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+    ) -> Result<(NaiveStrategy<M, S>, f64), MontyError> {
+        let (strategy, payoffs) =
+            self.second_best_response(&|h| Ok(vec![(f.make_move::<S>(h, &self.rules)?, 1.)]))?;
+
+        Ok((strategy, payoffs[0]))
+    }
+
+    /// Optimal pure strategy of the first player against `s`, and the resulting payoff of the
+    /// first player
+    pub fn best_response_to_second(
+        &self,
+        s: &impl SecondStrategy<M, S>,
+    ) -> (NaiveStrategy<M, F>, f64) {
+        self.try_best_response_to_second(s).unwrap()
+    }
+
+    pub fn try_best_response_to_second(
+        &self,
+        s: &impl SecondStrategy<M, S>,
+    ) -> Result<(NaiveStrategy<M, F>, f64), MontyError> {
+        let (strategy, payoffs) =
+            self.first_best_response(&|h| Ok(vec![(s.make_move::<F>(h, &self.rules)?, 1.)]))?;
+
+        Ok((strategy, payoffs[0]))
+    }
+
+    /// How much both players together gain by switching to their best responses (NashConv),
+    /// zero exactly at an equilibrium
+    pub fn exploitability(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+    ) -> f64 {
+        self.try_exploitability(f, s).unwrap()
+    }
+
+    pub fn try_exploitability(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+    ) -> Result<f64, MontyError> {
+        self.policy_exploitability(
+            &|h| Ok(vec![(f.make_move::<S>(h, &self.rules)?, 1.)]),
+            &|h| Ok(vec![(s.make_move::<F>(h, &self.rules)?, 1.)]),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_tree::GameTree;
+    use crate::games::{Host, MatrixRules, MontyHall, Placement};
+    use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+
+    #[test]
+    fn best_responses_match_the_matrix() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Host));
+        let first = tree.list_all_first_strategies();
+        let second = tree.list_all_second_strategies();
+        let game = tree.strategy_matrix(&first, &second);
+
+        for (f, row) in first.iter().zip(game.iter()) {
+            let (s, value) = tree.best_response_to_first(f);
+            let worst = row.iter().copied().fold(f64::INFINITY, f64::min);
+
+            assert!((value - worst).abs() < 1e-9);
+            assert!((tree.simulate(f, &s) - worst).abs() < 1e-9);
+        }
+    }
+
+    #[test]
+    fn exploitability_is_the_gain_from_deviating() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Host));
+        let first = tree.list_all_first_strategies();
+        let second = tree.list_all_second_strategies();
+        let game = tree.strategy_matrix(&first, &second);
+
+        for (i, f) in first.iter().enumerate() {
+            for (j, s) in second.iter().enumerate() {
+                let best_row = (0..game.n_rows())
+                    .map(|k| game[k][j])
+                    .fold(f64::MIN, f64::max);
+                let best_col = game[i].iter().copied().fold(f64::MAX, f64::min);
+
+                assert!((tree.exploitability(f, s) - (best_row - best_col)).abs() < 1e-9);
+            }
+        }
+    }
+
+    #[test]
+    fn general_sum_players_maximize_their_own_payoffs() {
+        // Battle of the sexes: both pure profiles on the diagonal are equilibria
+        let tree = GameTree::from_rules(MatrixRules::bimatrix(
+            BimatrixGame::new(
+                PayoffMatrix::new(vec![vec![3., 0.], vec![0., 2.]]).unwrap(),
+                PayoffMatrix::new(vec![vec![2., 0.], vec![0., 3.]]).unwrap(),
+            )
+            .unwrap(),
+        ));
+        let first = tree.list_all_first_strategies();
+        let second = tree.list_all_second_strategies();
+        let game = tree.to_bimatrix();
+        let mut equilibria = 0;
+
+        for (i, f) in first.iter().enumerate() {
+            let (s, value) = tree.best_response_to_first(f);
+            let best = game.second[i].iter().copied().fold(f64::MIN, f64::max);
+
+            assert!((tree.simulate_payoffs(f, &s)[1] - best).abs() < 1e-9);
+            assert!((tree.simulate(f, &s) - value).abs() < 1e-9);
+
+            for (j, s) in second.iter().enumerate() {
+                let best_row = (0..2).map(|k| game.first[k][j]).fold(f64::MIN, f64::max);
+                let gain = best_row - game.first[i][j] + best - game.second[i][j];
+                let exploitability = tree.exploitability(f, s);
+
+                assert!((exploitability - gain).abs() < 1e-9);
+                if exploitability.abs() < 1e-9 {
+                    equilibria += 1;
+                }
+            }
+        }
+
+        assert_eq!(equilibria, 2);
+    }
+}
@@ -0,0 +1,237 @@
+use crate::error::MontyError;
+use crate::game_tree::rules::Move;
+use crate::game_tree::strategy::NaiveStrategy;
+use float_cmp::approx_eq;
+use std::hash::Hash;
+
+/// What follows a sequence of moves in a game of any number of players
+pub(super) enum Turn<M> {
+    RandomEvent(Vec<(M, f64)>),
+    /// The player with the given index chooses one of the moves
+    ToMove(usize, Vec<M>),
+    /// Payoffs of all the players
+    GameOver(Vec<f64>),
+}
+
+/// Node of a tree built from rules, where the children are indices of nodes
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub(super) enum Node<M: Move> {
+    RandomEvent(Vec<(M, usize, f64)>),
+    PlayerMoves(usize, Vec<(M, usize)>),
+    GameOver(Vec<f64>),
+}
+
+impl<M: Move> Node<M> {
+    fn set_child(&mut self, j: usize, u: usize) {
+        match self {
+            Node::RandomEvent(row) => row[j].1 = u,
+            Node::PlayerMoves(_, row) => row[j].1 = u,
+            Node::GameOver(_) => unreachable!("a leaf has no children"),
+        }
+    }
+}
+
+/// A node of any of the game trees, as the walks below see it
+pub(super) enum NodeView<'a, M> {
+    RandomEvent(&'a [(M, usize, f64)]),
+    PlayerMoves(usize, &'a [(M, usize)]),
+    GameOver(&'a [f64]),
+}
+
+/// Nodes of a game tree, the root first
+pub(super) trait Tree<M: Move> {
+    fn view(&self, v: usize) -> NodeView<'_, M>;
+}
+
+impl<M: Move> Tree<M> for [Node<M>] {
+    fn view(&self, v: usize) -> NodeView<'_, M> {
+        match &self[v] {
+            Node::RandomEvent(row) => NodeView::RandomEvent(row),
+            Node::PlayerMoves(player, row) => NodeView::PlayerMoves(*player, row),
+            Node::GameOver(payoffs) => NodeView::GameOver(payoffs),
+        }
+    }
+}
+
+/// Sequence of moves from the root together with what the players observed of them
+pub(super) trait Position<M: Move> {
+    fn push_move(&mut self, m: M);
+    fn pop_move(&mut self);
+}
+
+/// Position whose rules tell what follows it
+pub(super) trait Expand<M: Move>: Position<M> {
+    fn turn(&self) -> Result<Turn<M>, MontyError>;
+}
+
+/// Nodes of the tree of all the plays from `position` in depth-first order
+pub(super) fn build<M: Move>(position: &mut impl Expand<M>) -> Result<Vec<Node<M>>, MontyError> {
+    let mut nodes = vec![];
+
+    expand(&mut nodes, position)?;
+
+    Ok(nodes)
+}
+
+fn expand<M: Move>(
+    nodes: &mut Vec<Node<M>>,
+    position: &mut impl Expand<M>,
+) -> Result<(), MontyError> {
+    let index = nodes.len();
+    let moves: Vec<M> = match position.turn()? {
+        Turn::RandomEvent(events) => {
+            if events.is_empty() {
+                return Err(MontyError::NoMoves);
+            }
+
+            let total = events.iter().map(|(_, prob)| prob).sum();
+
+            if !approx_eq!(f64, total, 1., epsilon = 1e-9)
+                || events.iter().any(|(_, prob)| *prob < 0.)
+            {
+                return Err(MontyError::BadProbabilities(total));
+            }
+
+            nodes.push(Node::RandomEvent(
+                events.iter().map(|(m, p)| (*m, usize::MAX, *p)).collect(),
+            ));
+            events.into_iter().map(|(m, _)| m).collect()
+        }
+        Turn::ToMove(player, moves) => {
+            if moves.is_empty() {
+                return Err(MontyError::NoMoves);
+            }
+
+            nodes.push(Node::PlayerMoves(
+                player,
+                moves.iter().map(|m| (*m, usize::MAX)).collect(),
+            ));
+            moves
+        }
+        Turn::GameOver(payoffs) => {
+            nodes.push(Node::GameOver(payoffs));
+            return Ok(());
+        }
+    };
+
+    for (j, m) in moves.into_iter().enumerate() {
+        let u = nodes.len();
+        nodes[index].set_child(j, u);
+        position.push_move(m);
+        expand(nodes, position)?;
+        position.pop_move();
+    }
+
+    Ok(())
+}
+
+/// Expected payoffs in the subtree of `v` when the player to move at a position plays
+/// `choose(player, position)`
+pub(super) fn simulate<M: Move, P: Position<M>>(
+    tree: &(impl Tree<M> + ?Sized),
+    position: &mut P,
+    v: usize,
+    choose: &dyn Fn(usize, &P) -> Result<M, MontyError>,
+) -> Result<Vec<f64>, MontyError> {
+    match tree.view(v) {
+        NodeView::RandomEvent(row) => {
+            let mut sum: Vec<f64> = vec![];
+
+            for (m, u, p) in row {
+                position.push_move(*m);
+                let payoffs = simulate(tree, position, *u, choose)?;
+                position.pop_move();
+
+                sum.resize(payoffs.len(), 0.);
+                for (x, y) in sum.iter_mut().zip(payoffs) {
+                    *x += y * p;
+                }
+            }
+
+            Ok(sum)
+        }
+        NodeView::PlayerMoves(player, row) => {
+            let m = choose(player, position)?;
+
+            let u = row
+                .iter()
+                .copied()
+                .find_map(|(a, b)| if a == m { Some(b) } else { None })
+                .ok_or(MontyError::IllegalMove)?;
+
+            position.push_move(m);
+            let ans = simulate(tree, position, u, choose);
+            position.pop_move();
+            ans
+        }
+        NodeView::GameOver(payoffs) => Ok(Vec::from(payoffs)),
+    }
+}
+
+/// `None` if `strategy` has a move at every history of `player` it can reach from `v`, or else
+/// the strategies extending it with every move at the first history it misses
+fn check_strategy<M: Move, O: Clone + Eq + Hash, P: Position<M>>(
+    tree: &(impl Tree<M> + ?Sized),
+    player: usize,
+    history: &impl Fn(&P) -> Vec<O>,
+    position: &mut P,
+    strategy: &NaiveStrategy<M, O>,
+    v: usize,
+) -> Option<Vec<NaiveStrategy<M, O>>> {
+    let visit = |position: &mut P, m: M, u: usize| {
+        position.push_move(m);
+        let ans = check_strategy(tree, player, history, position, strategy, u);
+        position.pop_move();
+        ans
+    };
+
+    match tree.view(v) {
+        NodeView::PlayerMoves(p, row) if p == player => {
+            let known = history(position);
+
+            match strategy.get(&known) {
+                None => Some(
+                    row.iter()
+                        .map(|(m, _)| {
+                            let mut q = strategy.clone();
+                            q.insert(known.clone(), *m);
+                            q
+                        })
+                        .collect(),
+                ),
+                Some(m) => visit(position, *m, row.iter().find(|(rm, _)| rm == m).unwrap().1),
+            }
+        }
+        NodeView::PlayerMoves(_, row) => row
+            .iter()
+            .map(|(m, u)| visit(position, *m, *u))
+            .find(|w| w.is_some())
+            .flatten(),
+        NodeView::RandomEvent(row) => row
+            .iter()
+            .map(|(m, u, _)| visit(position, *m, *u))
+            .find(|w| w.is_some())
+            .flatten(),
+        NodeView::GameOver(_) => None,
+    }
+}
+
+/// All the pure strategies of `player`, who knows `history(position)` when it is their turn
+pub(super) fn list_strategies<M: Move, O: Clone + Eq + Hash, P: Position<M>>(
+    tree: &(impl Tree<M> + ?Sized),
+    player: usize,
+    root: impl Fn() -> P,
+    history: impl Fn(&P) -> Vec<O>,
+) -> Vec<NaiveStrategy<M, O>> {
+    let mut ans = Vec::new();
+    let mut stack = vec![NaiveStrategy::new()];
+
+    while let Some(s) = stack.pop() {
+        match check_strategy(tree, player, &history, &mut root(), &s, 0) {
+            None => ans.push(s),
+            Some(updated) => stack.extend_from_slice(&updated),
+        }
+    }
+
+    ans
+}
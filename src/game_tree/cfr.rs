@@ -0,0 +1,338 @@
+use super::best_response::Policy;
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::BehavioralStrategy;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum CfrVariant {
+    /// Regret matching with simultaneous updates and uniform averaging
+    #[default]
+    Vanilla,
+    /// Regret matching+ with alternating updates and linear averaging
+    Plus,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct InfoSet<M: Move> {
+    moves: Vec<M>,
+    regrets: Vec<f64>,
+    /// Current strategy, fixed for the duration of a pass over the tree
+    strategy: Vec<f64>,
+    strategy_sum: Vec<f64>,
+}
+
+impl<M: Move> InfoSet<M> {
+    fn new(moves: Vec<M>) -> Self {
+        let n = moves.len();
+
+        Self {
+            moves,
+            regrets: vec![0.; n],
+            strategy: vec![1. / n as f64; n],
+            strategy_sum: vec![0.; n],
+        }
+    }
+
+    /// Updates the current strategy by regret matching
+    fn refresh(&mut self) {
+        let total: f64 = self.regrets.iter().map(|r| r.max(0.)).sum();
+
+        self.strategy = if total > 0. {
+            self.regrets.iter().map(|r| r.max(0.) / total).collect()
+        } else {
+            vec![1. / self.moves.len() as f64; self.moves.len()]
+        };
+    }
+
+    fn average(&self) -> Vec<(M, f64)> {
+        let total: f64 = self.strategy_sum.iter().sum();
+
+        self.moves
+            .iter()
+            .zip(&self.strategy_sum)
+            .map(|(m, s)| {
+                if total > 0. {
+                    (*m, s / total)
+                } else {
+                    (*m, 1. / self.moves.len() as f64)
+                }
+            })
+            .collect()
+    }
+}
+
+fn uniform<M: Move>(moves: Vec<M>) -> Vec<(M, f64)> {
+    let n = moves.len() as f64;
+
+    moves.into_iter().map(|m| (m, 1. / n)).collect()
+}
+
+fn average<M: Move, O: Hash + Eq + Clone>(
+    infosets: &HashMap<Vec<O>, InfoSet<M>>,
+) -> BehavioralStrategy<M, O> {
+    infosets
+        .iter()
+        .map(|(history, infoset)| (history.clone(), infoset.average()))
+        .collect()
+}
+
+/// Counterfactual regret minimization over the nodes of a game tree
+///
+/// Information sets are the observation histories of the players
+pub struct Cfr<'a, M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> {
+    tree: &'a GameTree<M, F, S, R>,
+    variant: CfrVariant,
+    iterations: usize,
+    first: HashMap<Vec<F>, InfoSet<M>>,
+    second: HashMap<Vec<S>, InfoSet<M>>,
+}
+
+impl<'a, M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> Cfr<'a, M, F, S, R> {
+    pub fn new(tree: &'a GameTree<M, F, S, R>, variant: CfrVariant) -> Self {
+        Self::try_new(tree, variant).unwrap()
+    }
+
+    /// Solver of a zero-sum tree; the regrets only look at the first player's payoffs, so other
+    /// trees are refused with `NotZeroSum`
+    pub fn try_new(
+        tree: &'a GameTree<M, F, S, R>,
+        variant: CfrVariant,
+    ) -> Result<Self, MontyError> {
+        if !tree.is_zero_sum() {
+            return Err(MontyError::NotZeroSum);
+        }
+
+        Ok(Self {
+            tree,
+            variant,
+            iterations: 0,
+            first: HashMap::new(),
+            second: HashMap::new(),
+        })
+    }
+
+    pub fn iterations(&self) -> usize {
+        self.iterations
+    }
+
+    /// Walks the tree and returns the value of the current strategies for the first player
+    ///
+    /// `reach` is the probability to reach `v` due to the first player, the second player and
+    /// chance respectively
+    fn traverse(
+        &mut self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        reach: [f64; 3],
+        update: [bool; 2],
+    ) -> f64 {
+        let tree = self.tree;
+        let weight = match self.variant {
+            CfrVariant::Vanilla => 1.,
+            CfrVariant::Plus => self.iterations as f64 + 1.,
+        };
+
+        match &tree.nodes[v] {
+            RandomEvent(row) => {
+                let mut sum = 0.;
+
+                for (m, u, p) in row {
+                    play.push_move(*m);
+                    sum += p * self.traverse(play, *u, [reach[0], reach[1], reach[2] * p], update);
+                    play.pop_move();
+                }
+
+                sum
+            }
+            FirstMoves(row) => {
+                let history = Vec::from(play.to_first());
+                let strategy = self
+                    .first
+                    .entry(history.clone())
+                    .or_insert_with(|| InfoSet::new(row.iter().map(|(m, _)| *m).collect()))
+                    .strategy
+                    .clone();
+                let mut values = Vec::with_capacity(row.len());
+
+                for ((m, u), p) in row.iter().zip(&strategy) {
+                    play.push_move(*m);
+                    values.push(self.traverse(
+                        play,
+                        *u,
+                        [reach[0] * p, reach[1], reach[2]],
+                        update,
+                    ));
+                    play.pop_move();
+                }
+
+                let value: f64 = values.iter().zip(&strategy).map(|(x, p)| x * p).sum();
+
+                if update[0] {
+                    let infoset = self.first.get_mut(&history).unwrap();
+                    for (j, x) in values.iter().enumerate() {
+                        infoset.regrets[j] += reach[1] * reach[2] * (x - value);
+                        infoset.strategy_sum[j] += weight * reach[0] * strategy[j];
+                    }
+                }
+
+                value
+            }
+            SecondMoves(row) => {
+                let history = Vec::from(play.to_second());
+                let strategy = self
+                    .second
+                    .entry(history.clone())
+                    .or_insert_with(|| InfoSet::new(row.iter().map(|(m, _)| *m).collect()))
+                    .strategy
+                    .clone();
+                let mut values = Vec::with_capacity(row.len());
+
+                for ((m, u), p) in row.iter().zip(&strategy) {
+                    play.push_move(*m);
+                    values.push(self.traverse(
+                        play,
+                        *u,
+                        [reach[0], reach[1] * p, reach[2]],
+                        update,
+                    ));
+                    play.pop_move();
+                }
+
+                let value: f64 = values.iter().zip(&strategy).map(|(x, p)| x * p).sum();
+
+                if update[1] {
+                    let infoset = self.second.get_mut(&history).unwrap();
+                    for (j, x) in values.iter().enumerate() {
+                        infoset.regrets[j] += reach[0] * reach[2] * (value - x);
+                        infoset.strategy_sum[j] += weight * reach[1] * strategy[j];
+                    }
+                }
+
+                value
+            }
+            GameOver(x) => x[0],
+        }
+    }
+
+    /// Floors the regrets for CFR+ and recomputes the current strategies
+    fn refresh(&mut self) {
+        for infoset in self.first.values_mut().chain(self.second.values_mut()) {
+            if self.variant == CfrVariant::Plus {
+                for r in &mut infoset.regrets {
+                    *r = r.max(0.);
+                }
+            }
+
+            infoset.refresh();
+        }
+    }
+
+    /// Runs a single iteration and returns the value of the current strategies
+    pub fn iterate(&mut self) -> f64 {
+        let value = match self.variant {
+            CfrVariant::Vanilla => self.traverse(&mut Play::new(), 0, [1.; 3], [true, true]),
+            CfrVariant::Plus => {
+                self.traverse(&mut Play::new(), 0, [1.; 3], [true, false]);
+                self.refresh();
+                self.traverse(&mut Play::new(), 0, [1.; 3], [false, true])
+            }
+        };
+
+        self.refresh();
+        self.iterations += 1;
+
+        value
+    }
+
+    /// Runs `iterations` iterations calling `callback` after each one
+    pub fn run(&mut self, iterations: usize, mut callback: impl FnMut(&Self)) {
+        for _ in 0..iterations {
+            self.iterate();
+            callback(self);
+        }
+    }
+
+    pub fn average_first(&self) -> BehavioralStrategy<M, F> {
+        average(&self.first)
+    }
+
+    pub fn average_second(&self) -> BehavioralStrategy<M, S> {
+        average(&self.second)
+    }
+
+    /// Sum of the gains of both players from deviating from the average strategies, which play
+    /// uniformly at random where no iteration went yet (everywhere before the first one)
+    pub fn exploitability(&self) -> f64 {
+        self.try_exploitability().unwrap()
+    }
+
+    pub fn try_exploitability(&self) -> Result<f64, MontyError> {
+        let first = self.average_first();
+        let second = self.average_second();
+        let first: Policy<M, F> = &|h| {
+            Ok(first
+                .get(h)
+                .cloned()
+                .unwrap_or_else(|| uniform(self.tree.rules.ask_first(h))))
+        };
+        let second: Policy<M, S> = &|h| {
+            Ok(second
+                .get(h)
+                .cloned()
+                .unwrap_or_else(|| uniform(self.tree.rules.ask_second(h))))
+        };
+
+        self.tree.policy_exploitability(first, second)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{CyclicGame, KuhnPoker, MatrixRules};
+    use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+
+    #[test]
+    fn exploitability_before_iterating_is_the_uniform_one() {
+        let tree = GameTree::from_rules(CyclicGame::default());
+        let cfr = Cfr::new(&tree, CfrVariant::Vanilla);
+
+        assert_eq!(cfr.try_exploitability(), Ok(0.));
+
+        let tree = GameTree::from_rules(KuhnPoker::default());
+        let cfr = Cfr::new(&tree, CfrVariant::Plus);
+
+        assert!(cfr.exploitability() > 0.);
+    }
+
+    #[test]
+    fn exploitability_decreases_on_kuhn_poker() {
+        let tree = GameTree::from_rules(KuhnPoker::default());
+        let mut cfr = Cfr::new(&tree, CfrVariant::Plus);
+        let start = cfr.exploitability();
+
+        cfr.run(500, |_| {});
+
+        assert!(cfr.exploitability() < start / 10.);
+    }
+
+    #[test]
+    fn rejects_general_sum_games() {
+        let tree = GameTree::from_rules(MatrixRules::bimatrix(
+            BimatrixGame::new(
+                PayoffMatrix::new(vec![vec![3., 0.], vec![0., 2.]]).unwrap(),
+                PayoffMatrix::new(vec![vec![2., 0.], vec![0., 3.]]).unwrap(),
+            )
+            .unwrap(),
+        ));
+
+        assert!(matches!(
+            Cfr::try_new(&tree, CfrVariant::Vanilla),
+            Err(MontyError::NotZeroSum)
+        ));
+    }
+}
@@ -0,0 +1,211 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::BehavioralStrategy;
+use std::collections::HashMap;
+use std::fmt::Write;
+
+/// Probability of `m` at `history`; histories the strategy does not know are never reached
+fn probability<M: Move, O: Observation<M>>(
+    strategy: &BehavioralStrategy<M, O>,
+    history: &[O],
+    m: M,
+) -> f64 {
+    strategy
+        .get(history)
+        .and_then(|row| row.iter().find(|(x, _)| *x == m))
+        .map_or(0., |(_, p)| *p)
+}
+
+/// Label as a quoted DOT string
+fn quote(label: &str) -> String {
+    format!("\"{}\"", label.replace('\\', "\\\\").replace('"', "\\\""))
+}
+
+/// Behavioural strategies of both players, if any
+type Profile<'a, M, F, S> = Option<(&'a BehavioralStrategy<M, F>, &'a BehavioralStrategy<M, S>)>;
+
+/// Nodes of every information set of both players, in the order of the traversal
+struct InfoSets<F, S> {
+    first: HashMap<Vec<F>, Vec<usize>>,
+    second: HashMap<Vec<S>, Vec<usize>>,
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    fn write_dot(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        reach: f64,
+        profile: Profile<M, F, S>,
+        infosets: &mut InfoSets<F, S>,
+        out: &mut String,
+    ) {
+        // Edges as the move, the child, the label and the probability of the move
+        let edges: Vec<(M, usize, String, f64)> = match &self.nodes[v] {
+            RandomEvent(row) => {
+                writeln!(out, "  {v} [shape=circle, label=\"\"];").unwrap();
+
+                row.iter()
+                    .map(|(m, u, p)| (*m, *u, format!("{} ({p:.4})", self.rules.move_name(*m)), *p))
+                    .collect()
+            }
+            FirstMoves(row) => {
+                writeln!(out, "  {v} [shape=triangle, label=\"1\"];").unwrap();
+                infosets
+                    .first
+                    .entry(Vec::from(play.to_first()))
+                    .or_default()
+                    .push(v);
+
+                row.iter()
+                    .map(|(m, u)| {
+                        let p = profile.map_or(1., |(f, _)| probability(f, play.to_first(), *m));
+                        (*m, *u, self.rules.move_name(*m), p)
+                    })
+                    .collect()
+            }
+            SecondMoves(row) => {
+                writeln!(out, "  {v} [shape=invtriangle, label=\"2\"];").unwrap();
+                infosets
+                    .second
+                    .entry(Vec::from(play.to_second()))
+                    .or_default()
+                    .push(v);
+
+                row.iter()
+                    .map(|(m, u)| {
+                        let p = profile.map_or(1., |(_, s)| probability(s, play.to_second(), *m));
+                        (*m, *u, self.rules.move_name(*m), p)
+                    })
+                    .collect()
+            }
+            GameOver([a, b]) => {
+                writeln!(out, "  {v} [shape=box, label=\"{}, {}\"];", a + 0., b + 0.).unwrap();
+
+                vec![]
+            }
+        };
+
+        for (m, u, label, p) in edges {
+            write!(out, "  {v} -> {u} [label={}", quote(&label)).unwrap();
+            if profile.is_some() {
+                write!(out, ", penwidth={:.3}", 0.25 + 4. * reach * p).unwrap();
+            }
+            writeln!(out, "];").unwrap();
+
+            play.push_move(m);
+            self.write_dot(play, u, reach * p, profile, infosets, out);
+            play.pop_move();
+        }
+    }
+
+    fn dot(&self, profile: Profile<M, F, S>) -> String {
+        let mut out = String::from("digraph {\n");
+        let mut infosets = InfoSets {
+            first: HashMap::new(),
+            second: HashMap::new(),
+        };
+
+        self.write_dot(&mut Play::new(), 0, 1., profile, &mut infosets, &mut out);
+
+        let mut groups: Vec<&Vec<usize>> = infosets
+            .first
+            .values()
+            .chain(infosets.second.values())
+            .collect();
+        groups.sort();
+
+        for group in groups {
+            for (a, b) in group.iter().zip(group.iter().skip(1)) {
+                writeln!(
+                    out,
+                    "  {a} -> {b} [style=dashed, dir=none, constraint=false];"
+                )
+                .unwrap();
+            }
+        }
+
+        out.push_str("}\n");
+
+        out
+    }
+
+    /// The tree in the Graphviz DOT language: circles are random events, triangles and inverted
+    /// triangles are the moves of the first and the second player, boxes are the outcomes, and
+    /// dashed lines link the nodes of the same information set
+    pub fn to_dot(&self) -> String {
+        self.dot(None)
+    }
+
+    /// The same as [`Self::to_dot`] with the width of every edge growing with the probability to
+    /// reach it when the players follow the given strategies
+    pub fn to_dot_with_profile(
+        &self,
+        first: &BehavioralStrategy<M, F>,
+        second: &BehavioralStrategy<M, S>,
+    ) -> String {
+        self.dot(Some((first, second)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{MatrixFirstView, MatrixMove, MatrixRules, MatrixSecondView};
+    use crate::matrix_game::PayoffMatrix;
+
+    /// Matching pennies with labels that need escaping; the nodes are the root (0), the second
+    /// player after each row (1 and 4) and the outcomes (2, 3, 5 and 6)
+    fn pennies() -> GameTree<MatrixMove, MatrixFirstView, MatrixSecondView, MatrixRules> {
+        let matrix = PayoffMatrix::new(vec![vec![1., -1.], vec![-1., 1.]])
+            .unwrap()
+            .with_labels(
+                vec![String::from("say \"heads\""), String::from("tails")],
+                vec![String::from("back\\slash"), String::from("tails")],
+            )
+            .unwrap();
+
+        GameTree::from_rules(MatrixRules::new(matrix))
+    }
+
+    #[test]
+    fn nodes_and_information_sets() {
+        let dot = pennies().to_dot();
+
+        assert!(dot.starts_with("digraph {\n") && dot.ends_with("}\n"));
+        assert!(dot.contains("  0 [shape=triangle, label=\"1\"];"));
+        assert!(dot.contains("  1 [shape=invtriangle, label=\"2\"];"));
+        assert!(dot.contains("  2 [shape=box, label=\"1, -1\"];"));
+        assert!(dot.contains("  1 -> 4 [style=dashed, dir=none, constraint=false];"));
+        assert!(!dot.contains("penwidth"));
+    }
+
+    #[test]
+    fn labels_are_escaped() {
+        let dot = pennies().to_dot();
+
+        assert!(dot.contains("  0 -> 1 [label=\"say \\\"heads\\\"\"];"));
+        assert!(dot.contains("  1 -> 2 [label=\"back\\\\slash\"];"));
+    }
+
+    #[test]
+    fn edges_grow_with_the_probability_to_reach_them() {
+        let first: BehavioralStrategy<MatrixMove, MatrixFirstView> = [(
+            vec![],
+            vec![(MatrixMove::Row(0), 1.), (MatrixMove::Row(1), 0.)],
+        )]
+        .into();
+        let second: BehavioralStrategy<MatrixMove, MatrixSecondView> = [(
+            vec![MatrixSecondView::from(MatrixMove::Row(0))],
+            vec![(MatrixMove::Column(0), 0.5), (MatrixMove::Column(1), 0.5)],
+        )]
+        .into();
+        let dot = pennies().to_dot_with_profile(&first, &second);
+
+        assert!(dot.contains("  0 -> 1 [label=\"say \\\"heads\\\"\", penwidth=4.250];"));
+        assert!(dot.contains("  0 -> 4 [label=\"tails\", penwidth=0.250];"));
+        assert!(dot.contains("  1 -> 2 [label=\"back\\\\slash\", penwidth=2.250];"));
+        assert!(dot.contains("  4 -> 6 [label=\"tails\", penwidth=0.250];"));
+    }
+}
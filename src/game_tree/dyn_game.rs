@@ -0,0 +1,252 @@
+use crate::error::MontyError;
+use crate::game_tree::interactive::Seat;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::{to_mixed, BehavioralStrategy, NaiveStrategy};
+use crate::game_tree::GameTree;
+use crate::matrix_game::{try_solve_zero_sum, BimatrixGame, Equilibrium, PayoffMatrix};
+use std::any::Any;
+use std::io::{BufRead, Write};
+
+/// Strategy of a player of a `DynGame`: a `NaiveStrategy` or a `BehavioralStrategy` over the
+/// move and observation types of the game
+pub type DynStrategy = Box<dyn Any>;
+
+/// Object-safe view of a `GameTree` that hides its type parameters, so that games of different
+/// types can be stored together and handled by the same code
+pub trait DynGame {
+    fn is_zero_sum(&self) -> bool;
+
+    /// Pure strategies of the first player in the order of the rows of the matrix
+    fn first_strategies(&self) -> Vec<DynStrategy>;
+
+    /// Pure strategies of the second player in the order of the columns of the matrix
+    fn second_strategies(&self) -> Vec<DynStrategy>;
+
+    /// Human-readable description of a pure strategy, or `None` if it is not one of this game
+    fn describe(&self, strategy: &dyn Any) -> Option<String>;
+
+    /// Exact expected payoff of the first player
+    fn try_evaluate(&self, first: &dyn Any, second: &dyn Any) -> Result<f64, MontyError>;
+
+    fn try_to_matrix(&self) -> Result<PayoffMatrix, MontyError>;
+
+    fn try_to_bimatrix(&self) -> Result<BimatrixGame, MontyError>;
+
+    /// Equilibrium of the normal form of a zero-sum game, or `NotZeroSum` for other games
+    fn try_solve(&self) -> Result<Equilibrium, MontyError>;
+
+    fn to_dot(&self) -> String;
+
+    fn to_json(&self) -> String;
+
+    fn to_efg(&self) -> String;
+
+    /// Plays up to `rounds` games of a human at `seat` against the equilibrium strategy of the
+    /// zero-sum game, reporting the running average payoff; returns the average payoff of the
+    /// human, or `None` if they quit before finishing a game, and `NotZeroSum` for other games
+    fn try_play(
+        &self,
+        seat: Seat,
+        rounds: usize,
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+    ) -> Result<Option<f64>, MontyError>;
+}
+
+/// Builds the tree of the game and hides its type
+pub fn build<M, F, S, R>(rules: R) -> Result<Box<dyn DynGame>, MontyError>
+where
+    M: Move + 'static,
+    F: Observation<M> + 'static,
+    S: Observation<M> + 'static,
+    R: GameRules<M, F, S> + 'static,
+{
+    Ok(Box::new(GameTree::try_from_rules(rules)?))
+}
+
+/// The strategy as a behavioural one; pure strategies play their moves with probability one
+fn behavioral<M: Move + 'static, O: Observation<M> + 'static>(
+    strategy: &dyn Any,
+) -> Result<BehavioralStrategy<M, O>, MontyError> {
+    if let Some(s) = strategy.downcast_ref::<BehavioralStrategy<M, O>>() {
+        Ok(s.clone())
+    } else if let Some(s) = strategy.downcast_ref::<NaiveStrategy<M, O>>() {
+        Ok(s.iter()
+            .map(|(history, m)| (history.clone(), vec![(*m, 1.)]))
+            .collect())
+    } else {
+        Err(MontyError::ForeignStrategy)
+    }
+}
+
+fn boxed<T: 'static>(strategies: Vec<T>) -> Vec<DynStrategy> {
+    strategies
+        .into_iter()
+        .map(|s| Box::new(s) as DynStrategy)
+        .collect()
+}
+
+impl<M, F, S, R> DynGame for GameTree<M, F, S, R>
+where
+    M: Move + 'static,
+    F: Observation<M> + 'static,
+    S: Observation<M> + 'static,
+    R: GameRules<M, F, S>,
+{
+    fn is_zero_sum(&self) -> bool {
+        GameTree::is_zero_sum(self)
+    }
+
+    fn first_strategies(&self) -> Vec<DynStrategy> {
+        boxed(self.list_all_first_strategies())
+    }
+
+    fn second_strategies(&self) -> Vec<DynStrategy> {
+        boxed(self.list_all_second_strategies())
+    }
+
+    fn describe(&self, strategy: &dyn Any) -> Option<String> {
+        strategy
+            .downcast_ref::<NaiveStrategy<M, F>>()
+            .map(|s| GameTree::describe(self, s))
+            .or_else(|| {
+                strategy
+                    .downcast_ref::<NaiveStrategy<M, S>>()
+                    .map(|s| GameTree::describe(self, s))
+            })
+    }
+
+    fn try_evaluate(&self, first: &dyn Any, second: &dyn Any) -> Result<f64, MontyError> {
+        if let (Some(f), Some(s)) = (
+            first.downcast_ref::<NaiveStrategy<M, F>>(),
+            second.downcast_ref::<NaiveStrategy<M, S>>(),
+        ) {
+            return self.try_simulate(f, s);
+        }
+
+        self.try_expected_value(&behavioral(first)?, &behavioral(second)?)
+    }
+
+    fn try_to_matrix(&self) -> Result<PayoffMatrix, MontyError> {
+        GameTree::try_to_matrix(self)
+    }
+
+    fn try_to_bimatrix(&self) -> Result<BimatrixGame, MontyError> {
+        GameTree::try_to_bimatrix(self)
+    }
+
+    fn try_solve(&self) -> Result<Equilibrium, MontyError> {
+        if !GameTree::is_zero_sum(self) {
+            return Err(MontyError::NotZeroSum);
+        }
+
+        try_solve_zero_sum(&GameTree::try_to_matrix(self)?)
+    }
+
+    fn to_dot(&self) -> String {
+        GameTree::to_dot(self)
+    }
+
+    fn to_json(&self) -> String {
+        GameTree::to_json(self)
+    }
+
+    fn to_efg(&self) -> String {
+        GameTree::to_efg(self)
+    }
+
+    fn try_play(
+        &self,
+        seat: Seat,
+        rounds: usize,
+        mut input: &mut dyn BufRead,
+        mut output: &mut dyn Write,
+    ) -> Result<Option<f64>, MontyError> {
+        if !GameTree::is_zero_sum(self) {
+            return Err(MontyError::NotZeroSum);
+        }
+
+        let first = self.list_all_first_strategies();
+        let second = self.list_all_second_strategies();
+        let equilibrium = try_solve_zero_sum(&self.try_strategy_matrix(&first, &second)?)?;
+        let first = self.first_behavioral(&to_mixed(&first, &equilibrium.first));
+        let second = self.second_behavioral(&to_mixed(&second, &equilibrium.second));
+        let (k, value) = match seat {
+            Seat::First => (0, equilibrium.value),
+            Seat::Second => (1, -equilibrium.value),
+        };
+        let mut rng = rand::thread_rng();
+        let mut total = 0.;
+        let mut played = 0;
+
+        while played < rounds {
+            writeln!(output, "--- game {} ---", played + 1)?;
+
+            let Some(payoffs) =
+                self.play_interactive(seat, &first, &second, &mut rng, &mut input, &mut output)?
+            else {
+                break;
+            };
+
+            played += 1;
+            total += payoffs[k];
+            writeln!(
+                output,
+                "you got {:.4}, {:.4} on average over {played} games; the game is worth {value:.4} \
+                 to you",
+                payoffs[k],
+                total / played as f64
+            )?;
+        }
+
+        Ok((played > 0).then(|| total / played as f64))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{CyclicGame, Guess, MatrixRules};
+
+    #[test]
+    fn games_of_different_types() {
+        let games: Vec<Box<dyn DynGame>> = vec![
+            build(Guess::default()).unwrap(),
+            build(CyclicGame::new(3).with_ties(vec![0., 2., 0.])).unwrap(),
+        ];
+
+        for (game, value) in games.iter().zip([0.5, 2. / 9.]) {
+            let first = game.first_strategies();
+            let second = game.second_strategies();
+
+            assert!((game.try_solve().unwrap().value - value).abs() < 1e-6);
+            assert!(game.describe(first[0].as_ref()).is_some());
+            assert!(game
+                .try_evaluate(first[0].as_ref(), second[0].as_ref())
+                .is_ok());
+        }
+
+        assert_eq!(
+            games[0].try_evaluate(&0u8, &0u8),
+            Err(MontyError::ForeignStrategy)
+        );
+    }
+
+    #[test]
+    fn only_zero_sum_games_are_solved() {
+        let game = build(MatrixRules::bimatrix(
+            BimatrixGame::new(
+                PayoffMatrix::new(vec![vec![3., 0.], vec![0., 2.]]).unwrap(),
+                PayoffMatrix::new(vec![vec![2., 0.], vec![0., 3.]]).unwrap(),
+            )
+            .unwrap(),
+        ))
+        .unwrap();
+
+        assert_eq!(game.try_solve(), Err(MontyError::NotZeroSum));
+        assert_eq!(
+            game.try_play(Seat::Second, 1, &mut "0\n".as_bytes(), &mut vec![]),
+            Err(MontyError::NotZeroSum)
+        );
+    }
+}
@@ -0,0 +1,123 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::gambit::quote;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use std::collections::HashMap;
+use std::fmt::Write;
+use std::hash::Hash;
+
+/// Numbers of the information sets, the chance nodes and the outcomes written so far
+struct Numbering<F, S> {
+    first: HashMap<Vec<F>, usize>,
+    second: HashMap<Vec<S>, usize>,
+    chance: usize,
+    outcomes: usize,
+}
+
+/// Number of the information set of `history`, and whether it is new
+fn number<O: Clone + Eq + Hash>(
+    infosets: &mut HashMap<Vec<O>, usize>,
+    history: &[O],
+) -> (usize, bool) {
+    let n = infosets.len() + 1;
+
+    match infosets.get(history) {
+        Some(k) => (*k, false),
+        None => {
+            infosets.insert(history.to_vec(), n);
+            (n, true)
+        }
+    }
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    fn write_efg(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        numbering: &mut Numbering<F, S>,
+        out: &mut String,
+    ) {
+        let edges: Vec<(M, usize)> = match &self.nodes[v] {
+            RandomEvent(row) => {
+                numbering.chance += 1;
+                let actions: Vec<String> = row
+                    .iter()
+                    .map(|(m, _, p)| format!("{} {p}", quote(&self.rules.move_name(*m))))
+                    .collect();
+                writeln!(
+                    out,
+                    "c \"\" {} \"\" {{ {} }} 0",
+                    numbering.chance,
+                    actions.join(" ")
+                )
+                .unwrap();
+
+                row.iter().map(|(m, u, _)| (*m, *u)).collect()
+            }
+            FirstMoves(row) | SecondMoves(row) => {
+                let (player, (k, new), history) = match &self.nodes[v] {
+                    FirstMoves(_) => (
+                        1,
+                        number(&mut numbering.first, play.to_first()),
+                        format!("{:?}", play.to_first()),
+                    ),
+                    _ => (
+                        2,
+                        number(&mut numbering.second, play.to_second()),
+                        format!("{:?}", play.to_second()),
+                    ),
+                };
+
+                write!(out, "p \"\" {player} {k}").unwrap();
+                // Actions are listed once per information set
+                if new {
+                    let actions: Vec<String> = row
+                        .iter()
+                        .map(|(m, _)| quote(&self.rules.move_name(*m)))
+                        .collect();
+                    write!(out, " {} {{ {} }}", quote(&history), actions.join(" ")).unwrap();
+                }
+                writeln!(out, " 0").unwrap();
+
+                row.clone()
+            }
+            GameOver([a, b]) => {
+                numbering.outcomes += 1;
+                // Adding zero turns negative zeros into zeros
+                writeln!(
+                    out,
+                    "t \"\" {} \"\" {{ {}, {} }}",
+                    numbering.outcomes,
+                    a + 0.,
+                    b + 0.
+                )
+                .unwrap();
+
+                vec![]
+            }
+        };
+
+        for (m, u) in edges {
+            play.push_move(m);
+            self.write_efg(play, u, numbering, out);
+            play.pop_move();
+        }
+    }
+
+    /// Extensive form of the game in the Gambit `.efg` format; the information sets are the
+    /// observation histories of the players
+    pub fn to_efg(&self) -> String {
+        let mut out = String::from("EFG 2 R \"\" { \"Player 1\" \"Player 2\" }\n\"\"\n\n");
+        let mut numbering = Numbering {
+            first: HashMap::new(),
+            second: HashMap::new(),
+            chance: 0,
+            outcomes: 0,
+        };
+
+        self.write_efg(&mut Play::new(), 0, &mut numbering, &mut out);
+
+        out
+    }
+}
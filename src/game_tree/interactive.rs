@@ -0,0 +1,222 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::{sample_move, BehavioralStrategy};
+use rand::Rng;
+use std::fmt::Debug;
+use std::io::{BufRead, Write};
+
+/// Which player a human plays for
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Seat {
+    #[default]
+    First,
+    Second,
+}
+
+/// Asks the human to choose one of the moves; `None` if they quit
+fn ask<M: Move>(
+    history: &impl Debug,
+    row: &[(M, usize)],
+    name: impl Fn(M) -> String,
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+) -> Result<Option<M>, MontyError> {
+    writeln!(output, "you know: {history:?}")?;
+    for (j, (m, _)) in row.iter().enumerate() {
+        writeln!(output, "  {j}: {}", name(*m))?;
+    }
+
+    loop {
+        write!(output, "your move (q to quit)> ")?;
+        output.flush()?;
+
+        let mut line = String::new();
+        if input.read_line(&mut line)? == 0 {
+            return Ok(None);
+        }
+
+        match line.trim() {
+            "q" => return Ok(None),
+            answer => match answer.parse::<usize>() {
+                Ok(j) if j < row.len() => return Ok(Some(row[j].0)),
+                _ => writeln!(output, "choose a number from 0 to {}", row.len() - 1)?,
+            },
+        }
+    }
+}
+
+/// The move of a computer player at `history`; histories the strategy does not know are played
+/// uniformly at random
+fn pick<M: Move, O: Observation<M>>(
+    strategy: &BehavioralStrategy<M, O>,
+    history: &[O],
+    row: &[(M, usize)],
+    rng: &mut impl Rng,
+) -> Result<M, MontyError> {
+    match strategy.get(history) {
+        Some(distribution) if distribution.iter().any(|(_, p)| *p > 0.) => {
+            sample_move(distribution, rng)
+        }
+        _ => Ok(row[rng.gen_range(0..row.len())].0),
+    }
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    /// What the human sees of a move
+    fn reveal(&self, seat: Seat, m: M) -> String {
+        match seat {
+            Seat::First => format!("{:?}", F::from(m)),
+            Seat::Second => format!("{:?}", S::from(m)),
+        }
+    }
+
+    /// Plays a game between a human sitting at `seat` and the computer following `first` or
+    /// `second`, whichever is not the human's; returns the payoffs, or `None` if the human quits
+    pub fn play_interactive(
+        &self,
+        seat: Seat,
+        first: &BehavioralStrategy<M, F>,
+        second: &BehavioralStrategy<M, S>,
+        rng: &mut impl Rng,
+        input: &mut impl BufRead,
+        output: &mut impl Write,
+    ) -> Result<Option<[f64; 2]>, MontyError> {
+        let mut play = Play::new();
+        let mut v = 0;
+
+        loop {
+            let (m, u) = match &self.nodes[v] {
+                RandomEvent(row) => {
+                    let distribution: Vec<(M, f64)> =
+                        row.iter().map(|(m, _, p)| (*m, *p)).collect();
+                    let m = sample_move(&distribution, rng)?;
+                    writeln!(output, "chance: {}", self.reveal(seat, m))?;
+
+                    let (_, u, _) = row
+                        .iter()
+                        .find(|(x, _, _)| *x == m)
+                        .ok_or(MontyError::IllegalMove)?;
+
+                    (m, *u)
+                }
+                FirstMoves(row) | SecondMoves(row) => {
+                    let mover = match &self.nodes[v] {
+                        FirstMoves(_) => Seat::First,
+                        _ => Seat::Second,
+                    };
+
+                    let m = if mover == seat {
+                        let chosen = match seat {
+                            Seat::First => ask(
+                                &play.to_first(),
+                                row,
+                                |m| self.rules.move_name(m),
+                                input,
+                                output,
+                            )?,
+                            Seat::Second => ask(
+                                &play.to_second(),
+                                row,
+                                |m| self.rules.move_name(m),
+                                input,
+                                output,
+                            )?,
+                        };
+
+                        match chosen {
+                            None => return Ok(None),
+                            Some(m) => m,
+                        }
+                    } else {
+                        let m = match mover {
+                            Seat::First => pick(first, play.to_first(), row, rng)?,
+                            Seat::Second => pick(second, play.to_second(), row, rng)?,
+                        };
+                        writeln!(output, "opponent: {}", self.reveal(seat, m))?;
+                        m
+                    };
+
+                    let (_, u) = row
+                        .iter()
+                        .find(|(x, _)| *x == m)
+                        .ok_or(MontyError::IllegalMove)?;
+
+                    (m, *u)
+                }
+                GameOver(payoffs) => return Ok(Some(*payoffs)),
+            };
+
+            play.push_move(m);
+            v = u;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{MatrixFirstView, MatrixMove, MatrixRules, MatrixSecondView};
+    use crate::matrix_game::PayoffMatrix;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    /// The first player's row decides the outcome, whatever column is played
+    fn tree() -> GameTree<MatrixMove, MatrixFirstView, MatrixSecondView, MatrixRules> {
+        let table = PayoffMatrix::new(vec![vec![1., 1.], vec![-1., -1.]])
+            .unwrap()
+            .with_labels(
+                vec![String::from("up"), String::from("down")],
+                vec![String::from("left"), String::from("right")],
+            )
+            .unwrap();
+
+        GameTree::from_rules(MatrixRules::new(table))
+    }
+
+    fn play(seat: Seat, script: &str) -> (Result<Option<[f64; 2]>, MontyError>, String) {
+        let mut output = vec![];
+        let result = tree().play_interactive(
+            seat,
+            &BehavioralStrategy::new(),
+            &BehavioralStrategy::new(),
+            &mut StdRng::seed_from_u64(0),
+            &mut script.as_bytes(),
+            &mut output,
+        );
+
+        (result, String::from_utf8(output).unwrap())
+    }
+
+    #[test]
+    fn quitting() {
+        assert_eq!(play(Seat::First, "q\n").0, Ok(None));
+        assert_eq!(play(Seat::First, "").0, Ok(None));
+    }
+
+    #[test]
+    fn finished_game_after_an_invalid_choice() {
+        let (result, output) = play(Seat::First, "7\nup\n1\n");
+
+        assert_eq!(result, Ok(Some([-1., 1.])));
+        assert!(output.contains("  0: up\n  1: down\n"));
+        assert_eq!(output.matches("choose a number from 0 to 1").count(), 2);
+        assert!(output.contains("opponent: Hidden"));
+    }
+
+    #[test]
+    fn computer_moves_must_be_legal() {
+        let first = BehavioralStrategy::from([(vec![], vec![(MatrixMove::Row(5), 1.)])]);
+        let result = tree().play_interactive(
+            Seat::Second,
+            &first,
+            &BehavioralStrategy::new(),
+            &mut StdRng::seed_from_u64(0),
+            &mut "0\n".as_bytes(),
+            &mut vec![],
+        );
+
+        assert_eq!(result, Err(MontyError::IllegalMove));
+    }
+}
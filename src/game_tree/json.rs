@@ -0,0 +1,112 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use std::fmt::Write;
+
+/// JSON string literal
+fn json_string(s: &str) -> String {
+    let mut ans = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => ans.push_str("\\\""),
+            '\\' => ans.push_str("\\\\"),
+            '\n' => ans.push_str("\\n"),
+            c if c.is_control() => write!(ans, "\\u{:04x}", c as u32).unwrap(),
+            c => ans.push(c),
+        }
+    }
+
+    ans.push('"');
+    ans
+}
+
+/// JSON number; JSON has no infinities and NaNs, which become `null`
+fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        format!("{}", x + 0.)
+    } else {
+        String::from("null")
+    }
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    fn write_json(&self, play: &mut Play<M, F, S>, v: usize, out: &mut String) {
+        let (kind, history, edges): (_, _, Vec<(M, usize, Option<f64>)>) = match &self.nodes[v] {
+            RandomEvent(row) => (
+                "chance",
+                None,
+                row.iter().map(|(m, u, p)| (*m, *u, Some(*p))).collect(),
+            ),
+            FirstMoves(row) => (
+                "first",
+                Some(format!("{:?}", play.to_first())),
+                row.iter().map(|(m, u)| (*m, *u, None)).collect(),
+            ),
+            SecondMoves(row) => (
+                "second",
+                Some(format!("{:?}", play.to_second())),
+                row.iter().map(|(m, u)| (*m, *u, None)).collect(),
+            ),
+            GameOver([a, b]) => {
+                write!(
+                    out,
+                    "{{\"type\":\"terminal\",\"payoffs\":[{},{}]}}",
+                    json_number(*a),
+                    json_number(*b)
+                )
+                .unwrap();
+                return;
+            }
+        };
+
+        write!(out, "{{\"type\":\"{kind}\"").unwrap();
+        if let Some(history) = history {
+            write!(out, ",\"history\":{}", json_string(&history)).unwrap();
+        }
+        out.push_str(",\"moves\":[");
+
+        for (j, (m, u, p)) in edges.into_iter().enumerate() {
+            if j > 0 {
+                out.push(',');
+            }
+            write!(out, "{{\"move\":{}", json_string(&self.rules.move_name(m))).unwrap();
+            if let Some(p) = p {
+                write!(out, ",\"probability\":{}", json_number(p)).unwrap();
+            }
+            out.push_str(",\"node\":");
+
+            play.push_move(m);
+            self.write_json(play, u, out);
+            play.pop_move();
+
+            out.push('}');
+        }
+
+        out.push_str("]}");
+    }
+
+    /// The tree as nested JSON objects; player nodes carry the observation history of the
+    /// player to move, which names their information set
+    pub fn to_json(&self) -> String {
+        let mut out = String::new();
+
+        self.write_json(&mut Play::new(), 0, &mut out);
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn literals() {
+        assert_eq!(json_string("a \"b\"\n\\"), r#""a \"b\"\n\\""#);
+        assert_eq!(json_number(-0.), "0");
+        assert_eq!(json_number(0.5), "0.5");
+        assert_eq!(json_number(f64::NAN), "null");
+        assert_eq!(json_number(f64::NEG_INFINITY), "null");
+    }
+}
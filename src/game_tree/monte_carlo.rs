@@ -0,0 +1,241 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Move, Observation, State};
+use crate::game_tree::strategy::{FirstStrategy, SecondStrategy};
+use float_cmp::approx_eq;
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::rngs::StdRng;
+use rand::{Rng, SeedableRng};
+
+/// Quantile of the standard normal distribution for the 95% confidence interval
+const Z_95: f64 = 1.959963984540054;
+
+/// Sample mean of the first player's payoff
+#[derive(Debug, Default, Copy, Clone, PartialOrd, PartialEq)]
+pub struct Estimate {
+    pub mean: f64,
+    pub standard_error: f64,
+    /// Normal approximation of the 95% confidence interval of the expected payoff
+    pub confidence_interval: [f64; 2],
+    pub samples: usize,
+}
+
+impl Estimate {
+    fn from_samples(
+        samples: usize,
+        mut sample: impl FnMut() -> Result<f64, MontyError>,
+    ) -> Result<Self, MontyError> {
+        if samples == 0 {
+            return Err(MontyError::BadParameter {
+                name: String::from("n"),
+                message: String::from("at least one sample is needed"),
+            });
+        }
+
+        // Welford's online algorithm
+        let mut mean = 0.;
+        let mut squares = 0.;
+
+        for k in 1..=samples {
+            let x = sample()?;
+            let delta = x - mean;
+            mean += delta / k as f64;
+            squares += delta * (x - mean);
+        }
+
+        let variance = if samples > 1 {
+            squares / (samples - 1) as f64
+        } else {
+            0.
+        };
+        let standard_error = (variance / samples as f64).sqrt();
+
+        Ok(Self {
+            mean,
+            standard_error,
+            confidence_interval: [mean - Z_95 * standard_error, mean + Z_95 * standard_error],
+            samples,
+        })
+    }
+}
+
+/// Plays one game straight from the rules, without building the tree, and returns the payoffs
+pub fn sample_play<M: Move, F: Observation<M>, S: Observation<M>>(
+    rules: &impl GameRules<M, F, S>,
+    f: &impl FirstStrategy<M, F>,
+    s: &impl SecondStrategy<M, S>,
+    rng: &mut impl Rng,
+) -> Result<[f64; 2], MontyError> {
+    let mut play = Play::new();
+
+    loop {
+        let m = match rules.ask_arbiter(play.to_arbiter()) {
+            State::RandomEvent => {
+                let events = rules.random_event(play.to_arbiter());
+                let total = events.iter().map(|(_, p)| p).sum();
+
+                if events.is_empty() {
+                    return Err(MontyError::NoMoves);
+                }
+                if !approx_eq!(f64, total, 1., epsilon = 1e-9)
+                    || events.iter().any(|(_, p)| *p < 0.)
+                {
+                    return Err(MontyError::BadProbabilities(total));
+                }
+
+                let index = WeightedIndex::new(events.iter().map(|(_, p)| *p))
+                    .map_err(|_| MontyError::BadProbabilities(total))?;
+                events[index.sample(rng)].0
+            }
+            State::FirstToMove => {
+                let m = f.make_move::<S>(play.to_first(), rules)?;
+                if !rules.ask_first(play.to_first()).contains(&m) {
+                    return Err(MontyError::IllegalMove);
+                }
+                m
+            }
+            State::SecondToMove => {
+                let m = s.make_move::<F>(play.to_second(), rules)?;
+                if !rules.ask_second(play.to_second()).contains(&m) {
+                    return Err(MontyError::IllegalMove);
+                }
+                m
+            }
+            State::GameOver(payoffs) => return Ok(payoffs),
+        };
+
+        play.push_move(m);
+    }
+}
+
+/// Estimates the first player's expected payoff from `n` games played straight from the rules
+///
+/// Only the random events are drawn from the seeded generator, and the strategies are pure, so the
+/// estimate is reproducible; fails with `BadParameter` when `n` is zero
+pub fn estimate_value<M: Move, F: Observation<M>, S: Observation<M>>(
+    rules: &impl GameRules<M, F, S>,
+    f: &impl FirstStrategy<M, F>,
+    s: &impl SecondStrategy<M, S>,
+    n: usize,
+    seed: u64,
+) -> Result<Estimate, MontyError> {
+    let mut rng = StdRng::seed_from_u64(seed);
+
+    Estimate::from_samples(n, || Ok(sample_play(rules, f, s, &mut rng)?[0]))
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    /// Plays one game with the random events drawn from `rng` and returns the payoffs
+    pub fn sample_play(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+        rng: &mut impl Rng,
+    ) -> [f64; 2] {
+        self.try_sample_play(f, s, rng).unwrap()
+    }
+
+    pub fn try_sample_play(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+        rng: &mut impl Rng,
+    ) -> Result<[f64; 2], MontyError> {
+        let mut play = Play::new();
+        let mut v = 0;
+
+        loop {
+            let (m, u) = match &self.nodes[v] {
+                RandomEvent(row) => {
+                    let index =
+                        WeightedIndex::new(row.iter().map(|(_, _, p)| *p)).map_err(|_| {
+                            MontyError::BadProbabilities(row.iter().map(|(_, _, p)| p).sum())
+                        })?;
+                    let (m, u, _) = row[index.sample(rng)];
+                    (m, u)
+                }
+                FirstMoves(row) => {
+                    let m = f.make_move::<S>(play.to_first(), &self.rules)?;
+                    let (_, u) = row
+                        .iter()
+                        .find(|(x, _)| *x == m)
+                        .ok_or(MontyError::IllegalMove)?;
+                    (m, *u)
+                }
+                SecondMoves(row) => {
+                    let m = s.make_move::<F>(play.to_second(), &self.rules)?;
+                    let (_, u) = row
+                        .iter()
+                        .find(|(x, _)| *x == m)
+                        .ok_or(MontyError::IllegalMove)?;
+                    (m, *u)
+                }
+                GameOver(payoffs) => return Ok(*payoffs),
+            };
+
+            play.push_move(m);
+            v = u;
+        }
+    }
+
+    /// Estimates the first player's expected payoff from `n` sampled games, see
+    /// [`estimate_value`] for the reproducibility
+    pub fn estimate_value(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+        n: usize,
+        seed: u64,
+    ) -> Estimate {
+        self.try_estimate_value(f, s, n, seed).unwrap()
+    }
+
+    pub fn try_estimate_value(
+        &self,
+        f: &impl FirstStrategy<M, F>,
+        s: &impl SecondStrategy<M, S>,
+        n: usize,
+        seed: u64,
+    ) -> Result<Estimate, MontyError> {
+        let mut rng = StdRng::seed_from_u64(seed);
+
+        Estimate::from_samples(n, || Ok(self.try_sample_play(f, s, &mut rng)?[0]))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Host, MontyHall, Placement};
+
+    #[test]
+    fn estimates_are_reproducible() {
+        let rules = MontyHall::new(3, 1, Host::Ignorant, Placement::Random);
+        let tree = GameTree::from_rules(rules);
+        let first = tree.list_all_first_strategies();
+        let second = tree.list_all_second_strategies();
+        let exact = tree.simulate(&first[1], &second[0]);
+        let estimate = tree.estimate_value(&first[1], &second[0], 10_000, 7);
+
+        assert_eq!(
+            estimate_value(&rules, &first[1], &second[0], 10_000, 7),
+            Ok(estimate)
+        );
+        assert_eq!(estimate.samples, 10_000);
+        assert!(estimate.confidence_interval[0] <= exact);
+        assert!(exact <= estimate.confidence_interval[1]);
+    }
+
+    #[test]
+    fn rejects_zero_samples() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Host));
+        let first = tree.list_all_first_strategies();
+        let second = tree.list_all_second_strategies();
+
+        assert!(matches!(
+            tree.try_estimate_value(&first[0], &second[0], 0, 7),
+            Err(MontyError::BadParameter { .. })
+        ));
+    }
+}
@@ -0,0 +1,357 @@
+use super::builder::{self, Expand, Node, Position, Turn};
+use crate::error::MontyError;
+use crate::game_tree::rules::Move;
+use crate::game_tree::strategy::NaiveStrategy;
+use std::fmt::Debug;
+use std::hash::Hash;
+use std::marker::PhantomData;
+
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+pub enum State {
+    RandomEvent,
+    /// The player with the given index is to move
+    ToMove(usize),
+    /// Payoffs of all the players
+    GameOver(Vec<f64>),
+}
+
+/// Rules of a game of any number of players; every player observes the moves through
+/// `observe`, so one observation type serves everybody
+pub trait Rules<M: Move, O: Copy + Eq + Hash + Debug> {
+    fn players(&self) -> usize;
+    fn ask_arbiter(&self, moves: &[M]) -> State;
+    fn observe(&self, player: usize, event: M) -> O;
+    fn ask_player(&self, player: usize, moves: &[O]) -> Vec<M>;
+    fn random_event(&self, moves: &[M]) -> Vec<(M, f64)>;
+}
+
+pub trait PlayerStrategy<M: Move, O> {
+    /// Move at the observation history `play`, or `IllegalMove` if the strategy has none
+    fn make_move(&self, player: usize, play: &[O]) -> Result<M, MontyError>;
+}
+
+impl<M: Move, O: Copy + Eq + Hash + Debug> PlayerStrategy<M, O> for NaiveStrategy<M, O> {
+    fn make_move(&self, _player: usize, play: &[O]) -> Result<M, MontyError> {
+        self.get(play).copied().ok_or(MontyError::IllegalMove)
+    }
+}
+
+/// Moves from the root and what every player observed of them, under the given rules
+#[derive(Debug, Clone, PartialEq)]
+struct Play<'a, M: Move, O, R> {
+    rules: &'a R,
+    moves: Vec<M>,
+    observations: Vec<Vec<O>>,
+}
+
+impl<'a, M: Move, O: Copy + Eq + Hash + Debug, R: Rules<M, O>> Play<'a, M, O, R> {
+    fn new(rules: &'a R) -> Self {
+        Self {
+            rules,
+            moves: vec![],
+            observations: vec![vec![]; rules.players()],
+        }
+    }
+}
+
+impl<M: Move, O: Copy + Eq + Hash + Debug, R: Rules<M, O>> Position<M> for Play<'_, M, O, R> {
+    fn push_move(&mut self, event: M) {
+        self.moves.push(event);
+        for (player, history) in self.observations.iter_mut().enumerate() {
+            history.push(self.rules.observe(player, event));
+        }
+    }
+
+    fn pop_move(&mut self) {
+        for history in &mut self.observations {
+            history.pop();
+        }
+        self.moves.pop();
+    }
+}
+
+impl<M: Move, O: Copy + Eq + Hash + Debug, R: Rules<M, O>> Expand<M> for Play<'_, M, O, R> {
+    fn turn(&self) -> Result<Turn<M>, MontyError> {
+        let players = self.rules.players();
+
+        match self.rules.ask_arbiter(&self.moves) {
+            State::RandomEvent => Ok(Turn::RandomEvent(self.rules.random_event(&self.moves))),
+            State::ToMove(player) if player >= players => Err(MontyError::UnknownPlayer(player)),
+            State::ToMove(player) => Ok(Turn::ToMove(
+                player,
+                self.rules.ask_player(player, &self.observations[player]),
+            )),
+            State::GameOver(payoffs) if payoffs.len() != players => Err(MontyError::PlayerCount {
+                expected: players,
+                found: payoffs.len(),
+            }),
+            State::GameOver(payoffs) => Ok(Turn::GameOver(payoffs)),
+        }
+    }
+}
+
+/// Payoffs of every pure strategy profile, stored in row-major order
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct PayoffTensor {
+    /// Number of pure strategies of every player
+    pub shape: Vec<usize>,
+    /// Payoffs of every player
+    pub payoffs: Vec<Vec<f64>>,
+}
+
+impl PayoffTensor {
+    fn flat_index(&self, profile: &[usize]) -> Result<usize, MontyError> {
+        if profile.len() != self.shape.len() {
+            return Err(MontyError::PlayerCount {
+                expected: self.shape.len(),
+                found: profile.len(),
+            });
+        }
+        if let Some(k) = (0..profile.len()).find(|k| profile[*k] >= self.shape[*k]) {
+            return Err(MontyError::BadParameter {
+                name: String::from("profile"),
+                message: format!(
+                    "player {k} has {} pure strategies, there is no strategy {}",
+                    self.shape[k], profile[k]
+                ),
+            });
+        }
+
+        Ok(profile
+            .iter()
+            .zip(&self.shape)
+            .fold(0, |acc, (i, n)| acc * n + i))
+    }
+
+    /// Payoff of `player` when every player `k` plays their pure strategy `profile[k]`
+    pub fn payoff(&self, player: usize, profile: &[usize]) -> f64 {
+        self.try_payoff(player, profile).unwrap()
+    }
+
+    pub fn try_payoff(&self, player: usize, profile: &[usize]) -> Result<f64, MontyError> {
+        let index = self.flat_index(profile)?;
+
+        self.payoffs
+            .get(player)
+            .map(|payoffs| payoffs[index])
+            .ok_or(MontyError::UnknownPlayer(player))
+    }
+}
+
+/// Game tree of a game with any number of players
+#[derive(Debug, Clone, PartialEq)]
+pub struct NPlayerTree<M: Move, O, R: Rules<M, O>>
+where
+    O: Copy + Eq + Hash + Debug,
+{
+    rules: R,
+    players: usize,
+    nodes: Vec<Node<M>>,
+    _phantom_o: PhantomData<O>,
+}
+
+impl<M: Move, O: Copy + Eq + Hash + Debug, R: Rules<M, O>> NPlayerTree<M, O, R> {
+    pub fn from_rules(rules: R) -> Self {
+        Self::try_from_rules(rules).unwrap()
+    }
+
+    pub fn try_from_rules(rules: R) -> Result<Self, MontyError> {
+        let players = rules.players();
+        let nodes = builder::build(&mut Play::new(&rules))?;
+
+        Ok(Self {
+            rules,
+            players,
+            nodes,
+            _phantom_o: PhantomData,
+        })
+    }
+
+    pub fn players(&self) -> usize {
+        self.players
+    }
+
+    /// Expected payoffs of all the players when player `k` follows `strategies[k]`
+    pub fn simulate(&self, strategies: &[&dyn PlayerStrategy<M, O>]) -> Vec<f64> {
+        self.try_simulate(strategies).unwrap()
+    }
+
+    pub fn try_simulate(
+        &self,
+        strategies: &[&dyn PlayerStrategy<M, O>],
+    ) -> Result<Vec<f64>, MontyError> {
+        if strategies.len() != self.players {
+            return Err(MontyError::PlayerCount {
+                expected: self.players,
+                found: strategies.len(),
+            });
+        }
+
+        builder::simulate(
+            &self.nodes[..],
+            &mut Play::new(&self.rules),
+            0,
+            &|player, play| strategies[player].make_move(player, &play.observations[player]),
+        )
+    }
+
+    pub fn list_all_strategies(&self, player: usize) -> Vec<NaiveStrategy<M, O>> {
+        builder::list_strategies(
+            &self.nodes[..],
+            player,
+            || Play::new(&self.rules),
+            |play| play.observations[player].clone(),
+        )
+    }
+
+    /// Normal form of the game: payoffs of every player for every profile of pure strategies
+    pub fn to_tensor(&self) -> PayoffTensor {
+        self.try_to_tensor().unwrap()
+    }
+
+    pub fn try_to_tensor(&self) -> Result<PayoffTensor, MontyError> {
+        let strategies: Vec<_> = (0..self.players)
+            .map(|k| self.list_all_strategies(k))
+            .collect();
+        let shape: Vec<usize> = strategies.iter().map(|s| s.len()).collect();
+        let mut payoffs = vec![vec![]; self.players];
+        let mut profile = vec![0; self.players];
+
+        loop {
+            let chosen: Vec<&dyn PlayerStrategy<M, O>> = profile
+                .iter()
+                .zip(&strategies)
+                .map(|(i, s)| &s[*i] as &dyn PlayerStrategy<M, O>)
+                .collect();
+
+            for (k, x) in self.try_simulate(&chosen)?.into_iter().enumerate() {
+                payoffs[k].push(x);
+            }
+
+            // Next profile in row-major order
+            match (0..self.players)
+                .rev()
+                .find(|k| profile[*k] + 1 < shape[*k])
+            {
+                None => break,
+                Some(k) => {
+                    profile[k] += 1;
+                    for x in &mut profile[k + 1..] {
+                        *x = 0;
+                    }
+                }
+            }
+        }
+
+        Ok(PayoffTensor { shape, payoffs })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::games::{Minority, MinorityMove};
+
+    /// One-player game where `mover` is to move, then the game ends with `payoffs` payoffs
+    #[derive(Debug, Clone, PartialEq)]
+    struct Broken {
+        mover: usize,
+        payoffs: usize,
+    }
+
+    impl Rules<usize, usize> for Broken {
+        fn players(&self) -> usize {
+            1
+        }
+
+        fn ask_arbiter(&self, moves: &[usize]) -> State {
+            match moves {
+                [] => State::ToMove(self.mover),
+                _ => State::GameOver(vec![0.; self.payoffs]),
+            }
+        }
+
+        fn observe(&self, _player: usize, event: usize) -> usize {
+            event
+        }
+
+        fn ask_player(&self, _player: usize, _moves: &[usize]) -> Vec<usize> {
+            vec![0, 1]
+        }
+
+        fn random_event(&self, _moves: &[usize]) -> Vec<(usize, f64)> {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn rejects_malformed_rules() {
+        assert_eq!(
+            NPlayerTree::try_from_rules(Broken {
+                mover: 1,
+                payoffs: 1
+            }),
+            Err(MontyError::UnknownPlayer(1))
+        );
+        assert_eq!(
+            NPlayerTree::try_from_rules(Broken {
+                mover: 0,
+                payoffs: 2
+            }),
+            Err(MontyError::PlayerCount {
+                expected: 1,
+                found: 2
+            })
+        );
+        assert!(NPlayerTree::try_from_rules(Broken {
+            mover: 0,
+            payoffs: 1
+        })
+        .is_ok());
+    }
+
+    #[test]
+    fn checks_tensor_indices() {
+        let tensor = NPlayerTree::from_rules(Minority::new(3)).to_tensor();
+
+        assert_eq!(tensor.shape, vec![2, 2, 2]);
+        assert_eq!(
+            tensor.try_payoff(0, &[0, 0, 1]),
+            Ok(tensor.payoff(0, &[0, 0, 1]))
+        );
+        assert_eq!(
+            tensor.try_payoff(0, &[0, 0]),
+            Err(MontyError::PlayerCount {
+                expected: 3,
+                found: 2
+            })
+        );
+        assert!(matches!(
+            tensor.try_payoff(0, &[0, 2, 0]),
+            Err(MontyError::BadParameter { .. })
+        ));
+        assert_eq!(
+            tensor.try_payoff(3, &[0, 0, 0]),
+            Err(MontyError::UnknownPlayer(3))
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_histories() {
+        let tree = NPlayerTree::from_rules(Minority::new(2));
+        let known: NaiveStrategy<MinorityMove, Option<bool>> = [(
+            vec![],
+            MinorityMove {
+                player: 0,
+                side: true,
+            },
+        )]
+        .into();
+        let unknown = NaiveStrategy::new();
+
+        assert_eq!(
+            tree.try_simulate(&[&known, &unknown]),
+            Err(MontyError::IllegalMove)
+        );
+    }
+}
@@ -0,0 +1,107 @@
+use super::GameTree;
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::{MixedStrategy, NaiveStrategy};
+use crate::matrix_game::{
+    merge_duplicates, try_solve_game, try_solve_zero_sum, Equilibrium, GameSolution, PayoffMatrix,
+};
+
+/// Normal form of a game where payoff-equivalent pure strategies are merged
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReducedMatrix<M: Move, F: Observation<M>, S: Observation<M>> {
+    pub matrix: PayoffMatrix,
+    /// Pure strategies of the first player merged into every row
+    pub first: Vec<Vec<NaiveStrategy<M, F>>>,
+    /// Pure strategies of the second player merged into every column
+    pub second: Vec<Vec<NaiveStrategy<M, S>>>,
+}
+
+fn lift<M: Move, O: Observation<M>>(
+    classes: &[Vec<NaiveStrategy<M, O>>],
+    distribution: &[f64],
+) -> MixedStrategy<M, O> {
+    classes
+        .iter()
+        .zip(distribution)
+        .filter(|(_, p)| **p > 0.)
+        .map(|(class, p)| (class[0].clone(), *p))
+        .collect()
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>> ReducedMatrix<M, F, S> {
+    pub fn solve_game(&self) -> GameSolution {
+        self.try_solve_game().unwrap()
+    }
+
+    pub fn try_solve_game(&self) -> Result<GameSolution, MontyError> {
+        try_solve_game(&self.matrix)
+    }
+
+    pub fn solve_zero_sum(&self) -> Equilibrium {
+        self.try_solve_zero_sum().unwrap()
+    }
+
+    pub fn try_solve_zero_sum(&self) -> Result<Equilibrium, MontyError> {
+        try_solve_zero_sum(&self.matrix)
+    }
+
+    /// Mixed strategy of the first player playing the first strategy of every row
+    pub fn first_mixed(&self, distribution: &[f64]) -> MixedStrategy<M, F> {
+        lift(&self.first, distribution)
+    }
+
+    /// Mixed strategy of the second player playing the first strategy of every column
+    pub fn second_mixed(&self, distribution: &[f64]) -> MixedStrategy<M, S> {
+        lift(&self.second, distribution)
+    }
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    pub fn reduced_matrix(&self) -> ReducedMatrix<M, F, S> {
+        self.try_reduced_matrix().unwrap()
+    }
+
+    pub fn try_reduced_matrix(&self) -> Result<ReducedMatrix<M, F, S>, MontyError> {
+        let first = self.list_all_first_strategies();
+        let second = self.list_all_second_strategies();
+        let matrix = self.try_strategy_matrix(&first, &second)?.with_labels(
+            first.iter().map(|s| self.describe(s)).collect(),
+            second.iter().map(|s| self.describe(s)).collect(),
+        )?;
+        let (matrix, rows, cols) = merge_duplicates(&matrix);
+
+        Ok(ReducedMatrix {
+            matrix,
+            first: rows
+                .into_iter()
+                .map(|r| r.into_iter().map(|i| first[i].clone()).collect())
+                .collect(),
+            second: cols
+                .into_iter()
+                .map(|c| c.into_iter().map(|j| second[j].clone()).collect())
+                .collect(),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::game_tree::GameTree;
+    use crate::games::{Host, MontyHall, Placement};
+    use crate::matrix_game::solve_zero_sum;
+
+    #[test]
+    fn reduction_keeps_the_value() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Ignorant, Placement::Host));
+        let reduced = tree.reduced_matrix();
+        let equilibrium = reduced.solve_zero_sum();
+        let value = tree.mixed_value(
+            &reduced.first_mixed(&equilibrium.first),
+            &reduced.second_mixed(&equilibrium.second),
+        );
+
+        assert!(reduced.matrix.n_rows() <= tree.to_matrix().n_rows());
+        assert!((equilibrium.value - solve_zero_sum(&tree.to_matrix()).value).abs() < 1e-6);
+        assert!((value - equilibrium.value).abs() < 1e-6);
+    }
+}
@@ -1,19 +1,30 @@
+pub use monty_hall_derive::Observation;
+use std::fmt::Debug;
 use std::hash::Hash;
 
-pub trait Move: Copy + Eq + Hash {}
+pub trait Move: Copy + Eq + Hash + Debug {}
 
-impl<T: Copy + Eq + Hash> Move for T {}
+impl<T: Copy + Eq + Hash + Debug> Move for T {}
 
-pub trait Observation<M>: Copy + Eq + Hash + From<M> {}
+pub trait Observation<M>: Copy + Eq + Hash + Debug + From<M> {}
 
-impl<M: Move, T: Copy + Eq + Hash + From<M>> Observation<M> for T {}
+impl<M: Move, T: Copy + Eq + Hash + Debug + From<M>> Observation<M> for T {}
 
 #[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum State {
     RandomEvent,
     FirstToMove,
     SecondToMove,
-    GameOver(f64),
+    /// Payoffs of the first and the second player
+    GameOver([f64; 2]),
+}
+
+impl State {
+    /// End of a zero-sum game where the first player wins `value`
+    pub fn zero_sum(value: f64) -> Self {
+        State::GameOver([value, -value])
+    }
 }
 
 pub trait GameRules<M: Move, F: Observation<M>, S: Observation<M>> {
@@ -21,4 +32,9 @@ pub trait GameRules<M: Move, F: Observation<M>, S: Observation<M>> {
     fn ask_first(&self, moves: &[F]) -> Vec<M>;
     fn ask_second(&self, moves: &[S]) -> Vec<M>;
     fn random_event(&self, moves: &[M]) -> Vec<(M, f64)>;
+
+    /// Name of the move in the strategy labels and the exported trees
+    fn move_name(&self, m: M) -> String {
+        format!("{m:?}")
+    }
 }
@@ -0,0 +1,330 @@
+use super::GameTreeNode::{FirstMoves, GameOver, RandomEvent, SecondMoves};
+use super::{GameTree, Play};
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Move, Observation};
+use crate::game_tree::strategy::BehavioralStrategy;
+use good_lp::solvers::DualValues;
+use good_lp::{
+    clarabel, variable, Expression, ProblemVariables, Solution, SolutionWithDual, SolverModel,
+    Variable,
+};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Information set of a player, i.e. the nodes sharing the same observation history
+struct InfoSet<M: Move> {
+    /// The sequence leading to the information set
+    parent: usize,
+    /// The sequences extending `parent` by a move available in the information set
+    sequences: Vec<(M, usize)>,
+}
+
+/// Sequences of one player: the empty sequence has index 0, every other sequence is an information
+/// set extended by a move
+struct Sequences<M: Move, O: Hash + Eq> {
+    count: usize,
+    infosets: HashMap<Vec<O>, InfoSet<M>>,
+}
+
+impl<M: Move, O: Hash + Eq + Clone> Sequences<M, O> {
+    fn new() -> Self {
+        Self {
+            count: 1,
+            infosets: HashMap::new(),
+        }
+    }
+
+    /// Registers the information set `history` reached by `parent` and returns its sequences
+    fn visit(
+        &mut self,
+        history: &[O],
+        parent: usize,
+        moves: &[M],
+    ) -> Result<Vec<usize>, MontyError> {
+        let count = &mut self.count;
+        let infoset = self
+            .infosets
+            .entry(Vec::from(history))
+            .or_insert_with(|| InfoSet {
+                parent,
+                sequences: moves
+                    .iter()
+                    .map(|m| {
+                        *count += 1;
+                        (*m, *count - 1)
+                    })
+                    .collect(),
+            });
+
+        if infoset.parent != parent || infoset.sequences.len() != moves.len() {
+            return Err(MontyError::ImperfectRecall);
+        }
+
+        moves
+            .iter()
+            .map(|m| {
+                infoset
+                    .sequences
+                    .iter()
+                    .find(|(sm, _)| sm == m)
+                    .map(|(_, seq)| *seq)
+                    .ok_or(MontyError::ImperfectRecall)
+            })
+            .collect()
+    }
+
+    /// Turns a realization plan into a behavioural strategy
+    fn behavioral(&self, plan: &[f64]) -> BehavioralStrategy<M, O> {
+        self.infosets
+            .iter()
+            .map(|(history, InfoSet { parent, sequences })| {
+                let total = plan[*parent];
+                let row = sequences
+                    .iter()
+                    .map(|(m, seq)| {
+                        if total > 1e-9 {
+                            (*m, (plan[*seq] / total).clamp(0., 1.))
+                        } else {
+                            (*m, 1. / sequences.len() as f64)
+                        }
+                    })
+                    .collect();
+                (history.clone(), row)
+            })
+            .collect()
+    }
+
+    /// `E x = e` constraints of the realization plan polytope
+    fn constraints(&self, plan: &[Variable]) -> Vec<(Expression, Expression)> {
+        let mut ans = vec![(plan[0].into(), 1.into())];
+
+        for InfoSet { parent, sequences } in self.infosets.values() {
+            ans.push((
+                sequences.iter().map(|(_, seq)| plan[*seq]).sum(),
+                plan[*parent].into(),
+            ));
+        }
+
+        ans
+    }
+}
+
+/// Solution of a game in behavioural strategies
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(
+    feature = "serde",
+    serde(bound(
+        serialize = "M: serde::Serialize, F: serde::Serialize, S: serde::Serialize",
+        deserialize = "M: serde::Deserialize<'de>, F: serde::Deserialize<'de>, \
+                       S: serde::Deserialize<'de>"
+    ))
+)]
+pub struct SequenceFormSolution<M: Move, F: Observation<M>, S: Observation<M>> {
+    /// Value of the game for the first player
+    pub value: f64,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::pairs"))]
+    pub first: BehavioralStrategy<M, F>,
+    #[cfg_attr(feature = "serde", serde(with = "crate::serialization::pairs"))]
+    pub second: BehavioralStrategy<M, S>,
+}
+
+impl<M: Move, F: Observation<M>, S: Observation<M>, R: GameRules<M, F, S>> GameTree<M, F, S, R> {
+    fn collect_sequences(
+        &self,
+        play: &mut Play<M, F, S>,
+        v: usize,
+        (seq1, seq2, prob): (usize, usize, f64),
+        first: &mut Sequences<M, F>,
+        second: &mut Sequences<M, S>,
+        payoffs: &mut HashMap<(usize, usize), f64>,
+    ) -> Result<(), MontyError> {
+        match &self.nodes[v] {
+            RandomEvent(row) => {
+                for (m, u, p) in row {
+                    play.push_move(*m);
+                    self.collect_sequences(
+                        play,
+                        *u,
+                        (seq1, seq2, prob * p),
+                        first,
+                        second,
+                        payoffs,
+                    )?;
+                    play.pop_move();
+                }
+            }
+            FirstMoves(row) => {
+                let moves: Vec<M> = row.iter().map(|(m, _)| *m).collect();
+                let sequences = first.visit(play.to_first(), seq1, &moves)?;
+
+                for ((m, u), seq) in row.iter().zip(sequences) {
+                    play.push_move(*m);
+                    self.collect_sequences(play, *u, (seq, seq2, prob), first, second, payoffs)?;
+                    play.pop_move();
+                }
+            }
+            SecondMoves(row) => {
+                let moves: Vec<M> = row.iter().map(|(m, _)| *m).collect();
+                let sequences = second.visit(play.to_second(), seq2, &moves)?;
+
+                for ((m, u), seq) in row.iter().zip(sequences) {
+                    play.push_move(*m);
+                    self.collect_sequences(play, *u, (seq1, seq, prob), first, second, payoffs)?;
+                    play.pop_move();
+                }
+            }
+            GameOver(x) => *payoffs.entry((seq1, seq2)).or_default() += prob * x[0],
+        }
+
+        Ok(())
+    }
+
+    /// Solves the game with the sequence-form linear program of Koller, Megiddo and von Stengel,
+    /// which is polynomial in the size of the tree
+    pub fn solve_sequence_form(&self) -> SequenceFormSolution<M, F, S> {
+        self.try_solve_sequence_form().unwrap()
+    }
+
+    /// Fails with `NotZeroSum` or `ImperfectRecall` on the games the linear program does not model
+    pub fn try_solve_sequence_form(&self) -> Result<SequenceFormSolution<M, F, S>, MontyError> {
+        if !self.is_zero_sum() {
+            return Err(MontyError::NotZeroSum);
+        }
+
+        let mut first = Sequences::new();
+        let mut second = Sequences::new();
+        let mut payoffs = HashMap::new();
+
+        self.collect_sequences(
+            &mut Play::new(),
+            0,
+            (0, 0, 1.),
+            &mut first,
+            &mut second,
+            &mut payoffs,
+        )?;
+
+        let mut problem = ProblemVariables::new();
+        let plan = problem.add_vector(variable().min(0), first.count);
+        // Dual variables of the second player's information sets and of the root
+        let mut duals: HashMap<&Vec<S>, Variable> = HashMap::new();
+        for history in second.infosets.keys() {
+            duals.insert(history, problem.add(variable()));
+        }
+        let root = problem.add(variable());
+
+        // For every sequence of the second player: (F^T q)_j <= (A^T x)_j
+        let mut lhs: Vec<Expression> = vec![Expression::default(); second.count];
+        let mut rhs: Vec<Expression> = vec![Expression::default(); second.count];
+
+        lhs[0] += root;
+        for (history, InfoSet { parent, sequences }) in &second.infosets {
+            let q = duals[history];
+            lhs[*parent] -= q;
+            for (_, seq) in sequences {
+                lhs[*seq] += q;
+            }
+        }
+        for ((seq1, seq2), payoff) in &payoffs {
+            rhs[*seq2] += *payoff * plan[*seq1];
+        }
+
+        let mut model = problem.maximise(root).using(clarabel);
+
+        for (a, b) in first.constraints(&plan) {
+            model = model.with(a.eq(b));
+        }
+
+        let references: Vec<_> = lhs
+            .into_iter()
+            .zip(rhs)
+            .map(|(a, b)| model.add_constraint(a.leq(b)))
+            .collect();
+
+        let mut solution = model.solve()?;
+
+        let value = solution.value(root);
+        let first_plan: Vec<f64> = plan.iter().map(|x| solution.value(*x)).collect();
+        let dual = solution.compute_dual();
+        let second_plan: Vec<f64> = references.into_iter().map(|c| dual.dual(c).abs()).collect();
+
+        Ok(SequenceFormSolution {
+            value,
+            first: first.behavioral(&first_plan),
+            second: second.behavioral(&second_plan),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::rules::State;
+    use crate::games::{KuhnPoker, MatrixRules};
+    use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+
+    /// Observation revealing nothing, so the first player forgets their first move
+    #[derive(Debug, Copy, Clone, Eq, PartialEq, Hash)]
+    struct Nothing;
+
+    impl From<u8> for Nothing {
+        fn from(_: u8) -> Self {
+            Nothing
+        }
+    }
+
+    /// The first player moves twice, and their second move is not told apart by the first one
+    struct Forgetful;
+
+    impl GameRules<u8, Nothing, Nothing> for Forgetful {
+        fn ask_arbiter(&self, moves: &[u8]) -> State {
+            match moves {
+                [] | [_] => State::FirstToMove,
+                [a, b] => State::zero_sum(f64::from(a ^ b)),
+                _ => unreachable!(),
+            }
+        }
+
+        fn ask_first(&self, _moves: &[Nothing]) -> Vec<u8> {
+            vec![0, 1]
+        }
+
+        fn ask_second(&self, _moves: &[Nothing]) -> Vec<u8> {
+            unreachable!()
+        }
+
+        fn random_event(&self, _moves: &[u8]) -> Vec<(u8, f64)> {
+            unreachable!()
+        }
+    }
+
+    #[test]
+    fn kuhn_poker_value() {
+        let solution = GameTree::from_rules(KuhnPoker::default()).solve_sequence_form();
+
+        assert!((solution.value + 1. / 18.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_imperfect_recall() {
+        let tree = GameTree::from_rules(Forgetful);
+
+        assert_eq!(
+            tree.try_solve_sequence_form(),
+            Err(MontyError::ImperfectRecall)
+        );
+    }
+
+    #[test]
+    fn rejects_non_zero_sum_games() {
+        let game = BimatrixGame::new(
+            PayoffMatrix::new(vec![vec![3., 0.], vec![0., 2.]]).unwrap(),
+            PayoffMatrix::new(vec![vec![2., 0.], vec![0., 3.]]).unwrap(),
+        )
+        .unwrap();
+        let tree = GameTree::from_rules(MatrixRules::bimatrix(game));
+
+        assert_eq!(tree.try_solve_sequence_form(), Err(MontyError::NotZeroSum));
+    }
+}
@@ -1,34 +1,173 @@
-use std::collections::HashMap;
+use crate::error::MontyError;
 use crate::game_tree::rules::{GameRules, Move, Observation};
+use rand::distributions::{Distribution, WeightedIndex};
+use rand::Rng;
+use std::collections::HashMap;
 
 pub trait FirstStrategy<M: Move, F: Observation<M>> {
-    fn make_move<S: Observation<M>>(&self, play: &[F], rules: &impl GameRules<M, F, S>) -> M;
+    /// The move at the observation history `play`, or `MontyError::IllegalMove` when the strategy
+    /// has none
+    fn make_move<S: Observation<M>>(
+        &self,
+        play: &[F],
+        rules: &impl GameRules<M, F, S>,
+    ) -> Result<M, MontyError>;
 }
 
 pub trait SecondStrategy<M: Move, S: Observation<M>> {
-    fn make_move<F: Observation<M>>(&self, play: &[S], rules: &impl GameRules<M, F, S>) -> M;
+    /// The move at the observation history `play`, or `MontyError::IllegalMove` when the strategy
+    /// has none
+    fn make_move<F: Observation<M>>(
+        &self,
+        play: &[S],
+        rules: &impl GameRules<M, F, S>,
+    ) -> Result<M, MontyError>;
 }
 
 pub type NaiveStrategy<M, F> = HashMap<Vec<F>, M>;
 
+/// Probability distribution over moves for every observation history
+///
+/// It does not implement `FirstStrategy` and `SecondStrategy`, which must play the same move at the
+/// same history; use `GameTree::expected_value` or draw the moves with `sample_behavioral`
+pub type BehavioralStrategy<M, F> = HashMap<Vec<F>, Vec<(M, f64)>>;
+
+/// Probability distribution over pure strategies
+pub type MixedStrategy<M, F> = Vec<(NaiveStrategy<M, F>, f64)>;
+
+/// Human-readable description of a pure strategy listing the move for every history
+pub fn describe<M: Move, F: Observation<M>>(strategy: &NaiveStrategy<M, F>) -> String {
+    describe_with(strategy, |m| format!("{m:?}"))
+}
+
+/// Same as `describe` with the moves named by `name`; a strategy of a single turn is only named
+/// by its move
+pub fn describe_with<M: Move, F: Observation<M>>(
+    strategy: &NaiveStrategy<M, F>,
+    name: impl Fn(M) -> String,
+) -> String {
+    if let [m] = strategy.values().collect::<Vec<_>>()[..] {
+        return name(*m);
+    }
+
+    let mut turns: Vec<(usize, String)> = strategy
+        .iter()
+        .map(|(history, m)| (history.len(), format!("{history:?} -> {}", name(*m))))
+        .collect();
+    turns.sort();
+
+    if turns.is_empty() {
+        return String::from("(no moves)");
+    }
+
+    turns
+        .into_iter()
+        .map(|(_, turn)| turn)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+/// Pairs the pure strategies with their probabilities, e.g., as returned by a matrix game solver,
+/// dropping the ones that are never played
+pub fn to_mixed<M: Move, F: Observation<M>>(
+    strategies: &[NaiveStrategy<M, F>],
+    distribution: &[f64],
+) -> MixedStrategy<M, F> {
+    strategies
+        .iter()
+        .zip(distribution)
+        .filter(|(_, p)| **p > 0.)
+        .map(|(s, p)| (s.clone(), *p))
+        .collect()
+}
+
+/// Index drawn with the given weights, failing when they are empty, all zero or not finite
+fn sample_index(
+    weights: impl Iterator<Item = f64> + Clone,
+    rng: &mut impl Rng,
+) -> Result<usize, MontyError> {
+    let index = WeightedIndex::new(weights.clone().map(|p| p.max(0.)))
+        .map_err(|_| MontyError::BadProbabilities(weights.sum()))?;
+
+    Ok(index.sample(rng))
+}
+
+/// Draws a move from a distribution
+pub fn sample_move<M: Move>(
+    distribution: &[(M, f64)],
+    rng: &mut impl Rng,
+) -> Result<M, MontyError> {
+    Ok(distribution[sample_index(distribution.iter().map(|(_, p)| *p), rng)?].0)
+}
+
+/// Draws the move of a behavioural strategy at the observation history `play`
+pub fn sample_behavioral<M: Move, F: Observation<M>>(
+    strategy: &BehavioralStrategy<M, F>,
+    play: &[F],
+    rng: &mut impl Rng,
+) -> Result<M, MontyError> {
+    sample_move(strategy.get(play).ok_or(MontyError::IllegalMove)?, rng)
+}
+
+/// Draws a pure strategy to play a whole game with
+pub fn sample_pure<'a, M: Move, F: Observation<M>>(
+    mixed: &'a MixedStrategy<M, F>,
+    rng: &mut impl Rng,
+) -> Result<&'a NaiveStrategy<M, F>, MontyError> {
+    Ok(&mixed[sample_index(mixed.iter().map(|(_, p)| *p), rng)?].0)
+}
+
 impl<M: Move, F: Observation<M>> FirstStrategy<M, F> for NaiveStrategy<M, F> {
-    fn make_move<S: Observation<M>>(&self, play: &[F], _rules: &impl GameRules<M, F, S>) -> M {
-        match self.get(play) {
-            None => {
-                unreachable!()
-            }
-            Some(m) => *m,
-        }
+    fn make_move<S: Observation<M>>(
+        &self,
+        play: &[F],
+        _rules: &impl GameRules<M, F, S>,
+    ) -> Result<M, MontyError> {
+        self.get(play).copied().ok_or(MontyError::IllegalMove)
     }
 }
 
 impl<M: Move, S: Observation<M>> SecondStrategy<M, S> for NaiveStrategy<M, S> {
-    fn make_move<F: Observation<M>>(&self, play: &[S], _rules: &impl GameRules<M, F, S>) -> M {
-        match self.get(play) {
-            None => {
-                unreachable!()
-            }
-            Some(m) => *m,
-        }
+    fn make_move<F: Observation<M>>(
+        &self,
+        play: &[S],
+        _rules: &impl GameRules<M, F, S>,
+    ) -> Result<M, MontyError> {
+        self.get(play).copied().ok_or(MontyError::IllegalMove)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    #[test]
+    fn sampling_rejects_bad_distributions() {
+        let mut rng = StdRng::seed_from_u64(0);
+
+        assert_eq!(sample_move(&[(0u8, 0.), (1, 1.)], &mut rng), Ok(1));
+        assert_eq!(
+            sample_move(&[(0u8, 0.), (1, 0.)], &mut rng),
+            Err(MontyError::BadProbabilities(0.))
+        );
+        assert!(sample_move::<u8>(&[], &mut rng).is_err());
+
+        let mixed: MixedStrategy<u8, u8> = vec![(NaiveStrategy::new(), 0.)];
+
+        assert!(sample_pure(&mixed, &mut rng).is_err());
+    }
+
+    #[test]
+    fn behavioral_sampling_rejects_unknown_histories() {
+        let mut rng = StdRng::seed_from_u64(0);
+        let strategy: BehavioralStrategy<u8, u8> = [(vec![], vec![(3, 1.)])].into();
+
+        assert_eq!(sample_behavioral(&strategy, &[], &mut rng), Ok(3));
+        assert_eq!(
+            sample_behavioral(&strategy, &[3], &mut rng),
+            Err(MontyError::IllegalMove)
+        );
     }
 }
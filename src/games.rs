@@ -1,7 +1,17 @@
+pub mod cyclic;
 mod guess;
-pub mod rock_paper_scissors;
-mod twisted_rock_paper_scissors;
+mod kuhn_poker;
+mod leduc_holdem;
+mod matrix_rules;
+mod minority;
+mod monty_hall;
+mod multi_monty_hall;
 
+pub use cyclic::CyclicGame;
 pub use guess::Guess;
-pub use rock_paper_scissors::RockPaperScissors;
-pub use twisted_rock_paper_scissors::TwistedRockPaperScissors;
+pub use kuhn_poker::{KuhnFirstView, KuhnMove, KuhnPoker, KuhnSecondView};
+pub use leduc_holdem::{LeducFirstView, LeducHoldem, LeducMove, LeducSecondView};
+pub use matrix_rules::{MatrixFirstView, MatrixMove, MatrixRules, MatrixSecondView};
+pub use minority::{Minority, MinorityMove};
+pub use monty_hall::{ContestantView, Host, MontyHall, MontyMove, Placement};
+pub use multi_monty_hall::{MultiMontyHall, MultiMontyMove, MultiMontyView};
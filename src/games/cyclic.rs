@@ -0,0 +1,266 @@
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Observation, State};
+
+/// Both players show one of the gestures at the same time; the winner gets 1 from the loser, and
+/// the first player gets a tie payoff depending on the gesture when both show the same one
+#[derive(Debug, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct CyclicGame {
+    /// `beats[a][b]` when the gesture `a` beats the gesture `b`
+    beats: Vec<Vec<bool>>,
+    ties: Vec<f64>,
+}
+
+impl Default for CyclicGame {
+    /// Rock-paper-scissors
+    fn default() -> Self {
+        Self::new(3)
+    }
+}
+
+impl CyclicGame {
+    /// Balanced game of an odd number of gestures, where each gesture beats the ones that are an
+    /// odd number of steps before it (modulo `gestures`)
+    ///
+    /// The gestures are numbered as in rock (0), paper (1), scissors (2), and for five gestures
+    /// Spock (3) and lizard (4)
+    pub fn new(gestures: u8) -> Self {
+        Self::try_new(gestures).unwrap()
+    }
+
+    pub fn try_new(gestures: u8) -> Result<Self, MontyError> {
+        if gestures.is_multiple_of(2) {
+            return Err(MontyError::BadParameter {
+                name: String::from("gestures"),
+                message: format!("a balanced game needs an odd number of gestures, not {gestures}"),
+            });
+        }
+
+        Self::try_from_relation(
+            (0..gestures)
+                .map(|a| {
+                    (0..gestures)
+                        .map(|b| {
+                            (i16::from(a) - i16::from(b)).rem_euclid(i16::from(gestures)) % 2 == 1
+                        })
+                        .collect()
+                })
+                .collect(),
+        )
+    }
+
+    /// Game with an arbitrary dominance relation; distinct gestures beating neither one another
+    /// tie with a payoff of zero
+    pub fn from_relation(beats: Vec<Vec<bool>>) -> Self {
+        Self::try_from_relation(beats).unwrap()
+    }
+
+    pub fn try_from_relation(beats: Vec<Vec<bool>>) -> Result<Self, MontyError> {
+        let n = beats.len();
+        let bad = |message: String| MontyError::BadParameter {
+            name: String::from("beats"),
+            message,
+        };
+
+        if !(1..=256).contains(&n) {
+            return Err(bad(String::from(
+                "between 1 and 256 gestures are supported",
+            )));
+        }
+        for (a, row) in beats.iter().enumerate() {
+            if row.len() != n {
+                return Err(bad(format!(
+                    "row {a} has {} entries, the relation must be a square matrix",
+                    row.len()
+                )));
+            }
+            if row[a] {
+                return Err(bad(format!("gesture {a} cannot beat itself")));
+            }
+            if let Some(b) = (0..a).find(|b| row[*b] && beats[*b][a]) {
+                return Err(bad(format!("gestures {a} and {b} cannot beat one another")));
+            }
+        }
+
+        Ok(Self {
+            beats,
+            ties: vec![0.; n],
+        })
+    }
+
+    /// Sets the payoffs of the first player when both show the same gesture, e.g. `[0., 2., 0.]`
+    /// for rock-paper-scissors with a twist on paper ties
+    pub fn with_ties(self, ties: Vec<f64>) -> Self {
+        self.try_with_ties(ties).unwrap()
+    }
+
+    pub fn try_with_ties(mut self, ties: Vec<f64>) -> Result<Self, MontyError> {
+        if ties.len() != self.beats.len() {
+            return Err(MontyError::BadParameter {
+                name: String::from("ties"),
+                message: format!(
+                    "expected one tie payoff per gesture ({}), found {}",
+                    self.beats.len(),
+                    ties.len()
+                ),
+            });
+        }
+
+        self.ties = ties;
+        Ok(self)
+    }
+
+    pub fn gestures(&self) -> usize {
+        self.beats.len()
+    }
+
+    fn compare(&self, a: u8, b: u8) -> f64 {
+        let (a, b) = (usize::from(a), usize::from(b));
+
+        if a == b {
+            self.ties[a]
+        } else if self.beats[a][b] {
+            1.
+        } else if self.beats[b][a] {
+            -1.
+        } else {
+            0.
+        }
+    }
+
+    fn all(&self, f: fn(u8) -> PlayerGesture) -> Vec<PlayerGesture> {
+        (0..self.beats.len()).map(|x| f(x as u8)).collect()
+    }
+}
+
+/// A gesture is only revealed to the player showing it
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Observation)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[observation(
+    first = FirstIntent,
+    second = SecondIntent,
+    attrs(
+        derive(Ord, PartialOrd),
+        cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))
+    )
+)]
+pub enum PlayerGesture {
+    #[observation(first)]
+    First(u8),
+    #[observation(second)]
+    Second(u8),
+}
+
+impl PlayerGesture {
+    pub fn to_gesture(self) -> u8 {
+        match self {
+            PlayerGesture::First(x) | PlayerGesture::Second(x) => x,
+        }
+    }
+}
+
+impl GameRules<PlayerGesture, FirstIntent, SecondIntent> for CyclicGame {
+    fn ask_arbiter(&self, moves: &[PlayerGesture]) -> State {
+        match moves.len() {
+            0 => State::FirstToMove,
+            1 => State::SecondToMove,
+            2 => State::zero_sum(self.compare(moves[0].to_gesture(), moves[1].to_gesture())),
+            _ => unreachable!(),
+        }
+    }
+
+    fn ask_first(&self, _moves: &[FirstIntent]) -> Vec<PlayerGesture> {
+        self.all(PlayerGesture::First)
+    }
+
+    fn ask_second(&self, _moves: &[SecondIntent]) -> Vec<PlayerGesture> {
+        self.all(PlayerGesture::Second)
+    }
+
+    fn random_event(&self, _moves: &[PlayerGesture]) -> Vec<(PlayerGesture, f64)> {
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::GameTree;
+    use crate::matrix_game::solve_zero_sum;
+
+    #[test]
+    fn gestures_are_only_seen_by_their_player() {
+        assert_eq!(
+            FirstIntent::from(PlayerGesture::First(2)),
+            FirstIntent::First(2)
+        );
+        assert_eq!(
+            FirstIntent::from(PlayerGesture::Second(2)),
+            FirstIntent::Hidden
+        );
+        assert_eq!(
+            SecondIntent::from(PlayerGesture::First(2)),
+            SecondIntent::Hidden
+        );
+    }
+
+    #[test]
+    fn balanced_games_are_fair() {
+        for gestures in [3, 5, 101] {
+            let game = GameTree::from_rules(CyclicGame::new(gestures)).to_matrix();
+            let equilibrium = solve_zero_sum(&game);
+
+            assert!(equilibrium.value.abs() < 1e-6);
+            assert!(equilibrium
+                .first
+                .iter()
+                .all(|p| (p - 1. / f64::from(gestures)).abs() < 1e-6));
+        }
+    }
+
+    #[test]
+    fn ties_favour_the_first_player() {
+        let tree = GameTree::from_rules(CyclicGame::new(3).with_ties(vec![0., 2., 0.]));
+        let value = solve_zero_sum(&tree.to_matrix()).value;
+        let equilibrium = tree.to_bimatrix().lemke_howson(0);
+
+        assert!((value - 2. / 9.).abs() < 1e-6);
+        assert!((tree.solve_sequence_form().value - value).abs() < 1e-6);
+        assert!((equilibrium.payoffs[0] - value).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rock_paper_scissors_well() {
+        // The well beats rock and scissors, and loses to paper
+        let well = CyclicGame::from_relation(vec![
+            vec![false, false, true, false],
+            vec![true, false, false, true],
+            vec![false, true, false, false],
+            vec![true, false, true, false],
+        ]);
+        let equilibrium = solve_zero_sum(&GameTree::from_rules(well).to_matrix());
+
+        assert!(equilibrium.value.abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        let bad = |result: Result<CyclicGame, MontyError>| {
+            matches!(result, Err(MontyError::BadParameter { .. }))
+        };
+
+        assert!(bad(CyclicGame::try_new(4)));
+        assert!(bad(CyclicGame::try_from_relation(vec![])));
+        assert!(bad(CyclicGame::try_from_relation(vec![
+            vec![false, true],
+            vec![false],
+        ])));
+        assert!(bad(CyclicGame::try_from_relation(vec![vec![true]])));
+        assert!(bad(CyclicGame::try_from_relation(vec![
+            vec![false, true],
+            vec![true, false],
+        ])));
+        assert!(bad(CyclicGame::new(3).try_with_ties(vec![0., 2.])));
+        assert!(CyclicGame::new(3).try_with_ties(vec![0., 2., 0.]).is_ok());
+    }
+}
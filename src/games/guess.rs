@@ -1,7 +1,8 @@
-use crate::game_tree::rules::State::{FirstToMove, GameOver, RandomEvent};
+use crate::game_tree::rules::State::{FirstToMove, RandomEvent};
 use crate::game_tree::rules::{GameRules, State};
 
 #[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Guess {}
 
 impl GameRules<u8, u8, u8> for Guess {
@@ -9,7 +10,7 @@ impl GameRules<u8, u8, u8> for Guess {
         match moves.len() {
             0 => FirstToMove,
             1 => RandomEvent,
-            2 => GameOver(if moves[0] == moves[1] { 1. } else { -1. }),
+            2 => State::zero_sum(if moves[0] == moves[1] { 1. } else { -1. }),
             _ => unreachable!(),
         }
     }
@@ -26,3 +27,20 @@ impl GameRules<u8, u8, u8> for Guess {
         vec![(0, 0.25), (2, 0.75)]
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::GameTree;
+    use crate::matrix_game::solve_zero_sum;
+
+    #[test]
+    fn guessing_the_likely_number() {
+        let game = GameTree::from_rules(Guess::default()).to_matrix();
+        let equilibrium = solve_zero_sum(&game);
+        let two = game.row_labels().iter().position(|x| x == "2").unwrap();
+
+        assert!((equilibrium.value - 0.5).abs() < 1e-6);
+        assert!((equilibrium.first[two] - 1.).abs() < 1e-6);
+    }
+}
@@ -0,0 +1,155 @@
+use crate::game_tree::rules::{GameRules, Observation, State};
+
+/// Kuhn poker: each player antes one chip and gets one of the jack, the queen and the king, then
+/// there is a single round of betting with bets of one chip
+///
+/// The first player loses 1/18 per game in equilibrium
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct KuhnPoker {}
+
+/// Cards are ranked 0 (jack), 1 (queen) and 2 (king); each player only sees their own card and
+/// all the betting
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Observation)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[observation(
+    first = KuhnFirstView,
+    second = KuhnSecondView,
+    attrs(cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize)))
+)]
+pub enum KuhnMove {
+    Deal {
+        #[observation(first)]
+        first: u8,
+        #[observation(second)]
+        second: u8,
+    },
+    Check,
+    Bet,
+    Call,
+    Fold,
+}
+
+impl GameRules<KuhnMove, KuhnFirstView, KuhnSecondView> for KuhnPoker {
+    fn ask_arbiter(&self, moves: &[KuhnMove]) -> State {
+        use KuhnMove::{Bet, Call, Check, Fold};
+
+        let showdown = |stake: f64| match moves[0] {
+            KuhnMove::Deal { first, second } if first > second => State::zero_sum(stake),
+            _ => State::zero_sum(-stake),
+        };
+
+        match moves {
+            [] => State::RandomEvent,
+            [_] | [_, Check, Bet] => State::FirstToMove,
+            [_, Check] | [_, Bet] => State::SecondToMove,
+            [_, Check, Check] => showdown(1.),
+            [_, Bet, Call] | [_, Check, Bet, Call] => showdown(2.),
+            [_, Bet, Fold] => State::zero_sum(1.),
+            [_, Check, Bet, Fold] => State::zero_sum(-1.),
+            _ => unreachable!(),
+        }
+    }
+
+    fn ask_first(&self, moves: &[KuhnFirstView]) -> Vec<KuhnMove> {
+        match moves.len() {
+            1 => vec![KuhnMove::Check, KuhnMove::Bet],
+            3 => vec![KuhnMove::Call, KuhnMove::Fold],
+            _ => unreachable!(),
+        }
+    }
+
+    fn ask_second(&self, moves: &[KuhnSecondView]) -> Vec<KuhnMove> {
+        match moves {
+            [_, KuhnSecondView::Check] => vec![KuhnMove::Check, KuhnMove::Bet],
+            [_, KuhnSecondView::Bet] => vec![KuhnMove::Call, KuhnMove::Fold],
+            _ => unreachable!(),
+        }
+    }
+
+    fn random_event(&self, _moves: &[KuhnMove]) -> Vec<(KuhnMove, f64)> {
+        (0..3)
+            .flat_map(|first| {
+                (0..3)
+                    .filter(move |second| *second != first)
+                    .map(move |second| (KuhnMove::Deal { first, second }, 1. / 6.))
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::strategy::{to_mixed, BehavioralStrategy};
+    use crate::game_tree::GameTree;
+    use crate::matrix_game::solve_zero_sum;
+
+    const EPSILON: f64 = 1e-6;
+
+    fn probability<O: Observation<KuhnMove>>(
+        strategy: &BehavioralStrategy<KuhnMove, O>,
+        history: &[O],
+        m: KuhnMove,
+    ) -> f64 {
+        strategy[history]
+            .iter()
+            .find(|(x, _)| *x == m)
+            .map_or(0., |(_, p)| *p)
+    }
+
+    #[test]
+    fn equilibrium() {
+        let tree = GameTree::from_rules(KuhnPoker::default());
+        let first = tree.list_all_first_strategies();
+        let second = tree.list_all_second_strategies();
+        let equilibrium = solve_zero_sum(&tree.strategy_matrix(&first, &second));
+        let first = tree.first_behavioral(&to_mixed(&first, &equilibrium.first));
+        let second = tree.second_behavioral(&to_mixed(&second, &equilibrium.second));
+        let bet = |card| {
+            probability(
+                &first,
+                &[KuhnFirstView::Deal { first: card }],
+                KuhnMove::Bet,
+            )
+        };
+        let call = |card| {
+            probability(
+                &first,
+                &[
+                    KuhnFirstView::Deal { first: card },
+                    KuhnFirstView::Check,
+                    KuhnFirstView::Bet,
+                ],
+                KuhnMove::Call,
+            )
+        };
+        let respond = |card, action, m| {
+            probability(&second, &[KuhnSecondView::Deal { second: card }, action], m)
+        };
+        let alpha = bet(0);
+
+        assert!((equilibrium.value + 1. / 18.).abs() < EPSILON);
+
+        // The first player bets the jack with some alpha <= 1/3, never bets the queen, bets the
+        // king three times as often as the jack, and calls with the queen with alpha + 1/3
+        assert!(alpha < 1. / 3. + EPSILON);
+        assert!(bet(1) < EPSILON);
+        assert!((bet(2) - 3. * alpha).abs() < EPSILON);
+        assert!((call(1) - alpha - 1. / 3.).abs() < EPSILON);
+        assert!(call(0) < EPSILON);
+        assert!(call(2) > 1. - EPSILON);
+
+        // After a check, the second player bluffs with the jack a third of the time, never bets
+        // the queen and always bets the king
+        assert!((respond(0, KuhnSecondView::Check, KuhnMove::Bet) - 1. / 3.).abs() < EPSILON);
+        assert!(respond(1, KuhnSecondView::Check, KuhnMove::Bet) < EPSILON);
+        assert!(respond(2, KuhnSecondView::Check, KuhnMove::Bet) > 1. - EPSILON);
+
+        // Facing a bet, they fold the jack, call with the queen a third of the time and always
+        // call with the king
+        assert!(respond(0, KuhnSecondView::Bet, KuhnMove::Call) < EPSILON);
+        assert!((respond(1, KuhnSecondView::Bet, KuhnMove::Call) - 1. / 3.).abs() < EPSILON);
+        assert!(respond(2, KuhnSecondView::Bet, KuhnMove::Call) > 1. - EPSILON);
+    }
+}
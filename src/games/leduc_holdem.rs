@@ -0,0 +1,326 @@
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Observation, State};
+
+/// Leduc hold'em: each player antes one chip and gets a private card, then there is a round of
+/// betting, a public card and another round of betting; a private card pairing the public card
+/// wins, otherwise the higher rank wins
+#[derive(Debug, Copy, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct LeducHoldem {
+    ranks: u8,
+    suits: u8,
+    bets: [f64; 2],
+    raises: u8,
+}
+
+impl Default for LeducHoldem {
+    /// The standard game: a deck of two suits of three ranks, bets of 2 and 4 chips and a single
+    /// raise per round, where the first player loses about 0.0856 per game in equilibrium
+    fn default() -> Self {
+        Self::new(3, 2, [2., 4.], 1)
+    }
+}
+
+impl LeducHoldem {
+    /// Deck of `ranks` ranks with `suits` cards of each, bets of `bets[k]` chips in the round `k`
+    /// and at most `raises` raises of the opening bet per round
+    pub fn new(ranks: u8, suits: u8, bets: [f64; 2], raises: u8) -> Self {
+        Self::try_new(ranks, suits, bets, raises).unwrap()
+    }
+
+    pub fn try_new(ranks: u8, suits: u8, bets: [f64; 2], raises: u8) -> Result<Self, MontyError> {
+        if u16::from(ranks) * u16::from(suits) < 3 {
+            return Err(MontyError::BadParameter {
+                name: String::from(if ranks == 0 { "ranks" } else { "suits" }),
+                message: String::from("the deck must have at least three cards"),
+            });
+        }
+        if let Some(k) = (0..2).find(|k| !(bets[*k].is_finite() && bets[*k] > 0.)) {
+            return Err(MontyError::BadParameter {
+                name: String::from("bets"),
+                message: format!("the bet of round {k} must be positive, not {}", bets[k]),
+            });
+        }
+
+        Ok(Self {
+            ranks,
+            suits,
+            bets,
+            raises,
+        })
+    }
+
+    fn deck(&self) -> f64 {
+        f64::from(self.ranks) * f64::from(self.suits)
+    }
+
+    /// Follows the betting in `history`, which starts with the private cards
+    fn walk(&self, history: &[impl Action]) -> Betting {
+        let mut stakes = [1., 1.];
+        let mut round = 0;
+        let mut player = 0;
+        let mut bets = 0;
+        let mut acted = false;
+        let mut over = false;
+
+        for action in history[1..].iter().map(|x| x.action()) {
+            match action {
+                LeducMove::Public(_) => {
+                    round = 1;
+                    player = 0;
+                    bets = 0;
+                    acted = false;
+                    over = false;
+                }
+                LeducMove::Check => {
+                    over = acted;
+                    acted = true;
+                    player = 1 - player;
+                }
+                LeducMove::Bet => {
+                    stakes[player] = stakes[1 - player] + self.bets[round];
+                    bets += 1;
+                    acted = true;
+                    player = 1 - player;
+                }
+                LeducMove::Call => {
+                    stakes[player] = stakes[1 - player];
+                    over = true;
+                }
+                LeducMove::Fold => return Betting::Fold { player, stakes },
+                LeducMove::Deal { .. } => unreachable!(),
+            }
+        }
+
+        if !over {
+            let actions = if stakes[player] < stakes[1 - player] {
+                let mut actions = vec![LeducMove::Call, LeducMove::Fold];
+                if bets <= self.raises {
+                    actions.push(LeducMove::Bet);
+                }
+                actions
+            } else {
+                vec![LeducMove::Check, LeducMove::Bet]
+            };
+
+            Betting::ToAct { player, actions }
+        } else if round == 0 {
+            Betting::Deal
+        } else {
+            Betting::Showdown { stake: stakes[0] }
+        }
+    }
+}
+
+/// Cards are identified by their ranks, the suits only matter for the probabilities of the deals;
+/// each player sees their own card, the public card and all the betting
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Observation)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[observation(
+    first = LeducFirstView,
+    second = LeducSecondView,
+    attrs(
+        derive(Ord, PartialOrd),
+        cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))
+    )
+)]
+pub enum LeducMove {
+    Deal {
+        #[observation(first)]
+        first: u8,
+        #[observation(second)]
+        second: u8,
+    },
+    Public(u8),
+    Check,
+    /// Opens or raises the betting
+    Bet,
+    Call,
+    Fold,
+}
+
+/// Betting history shared by the moves and the observations of both players
+trait Action: Copy {
+    /// The move, with the private cards forgotten
+    fn action(self) -> LeducMove;
+}
+
+impl Action for LeducMove {
+    fn action(self) -> LeducMove {
+        self
+    }
+}
+
+macro_rules! impl_action {
+    ($view:ident) => {
+        impl Action for $view {
+            fn action(self) -> LeducMove {
+                match self {
+                    $view::Deal { .. } => LeducMove::Deal {
+                        first: 0,
+                        second: 0,
+                    },
+                    $view::Public(x) => LeducMove::Public(x),
+                    $view::Check => LeducMove::Check,
+                    $view::Bet => LeducMove::Bet,
+                    $view::Call => LeducMove::Call,
+                    $view::Fold => LeducMove::Fold,
+                }
+            }
+        }
+    };
+}
+
+impl_action!(LeducFirstView);
+impl_action!(LeducSecondView);
+
+enum Betting {
+    ToAct {
+        player: usize,
+        actions: Vec<LeducMove>,
+    },
+    /// The public card is dealt next
+    Deal,
+    Fold {
+        player: usize,
+        stakes: [f64; 2],
+    },
+    Showdown {
+        stake: f64,
+    },
+}
+
+impl GameRules<LeducMove, LeducFirstView, LeducSecondView> for LeducHoldem {
+    fn ask_arbiter(&self, moves: &[LeducMove]) -> State {
+        if moves.is_empty() {
+            return State::RandomEvent;
+        }
+
+        match self.walk(moves) {
+            Betting::ToAct { player: 0, .. } => State::FirstToMove,
+            Betting::ToAct { .. } => State::SecondToMove,
+            Betting::Deal => State::RandomEvent,
+            Betting::Fold { player: 0, stakes } => State::zero_sum(-stakes[0]),
+            Betting::Fold { stakes, .. } => State::zero_sum(stakes[1]),
+            Betting::Showdown { stake } => {
+                let (LeducMove::Deal { first, second }, Some(LeducMove::Public(public))) = (
+                    moves[0],
+                    moves.iter().find(|m| matches!(m, LeducMove::Public(_))),
+                ) else {
+                    unreachable!()
+                };
+                let strength = |card: u8| (card == *public, card);
+
+                match strength(first).cmp(&strength(second)) {
+                    std::cmp::Ordering::Less => State::zero_sum(-stake),
+                    std::cmp::Ordering::Equal => State::zero_sum(0.),
+                    std::cmp::Ordering::Greater => State::zero_sum(stake),
+                }
+            }
+        }
+    }
+
+    fn ask_first(&self, moves: &[LeducFirstView]) -> Vec<LeducMove> {
+        match self.walk(moves) {
+            Betting::ToAct { actions, .. } => actions,
+            _ => unreachable!(),
+        }
+    }
+
+    fn ask_second(&self, moves: &[LeducSecondView]) -> Vec<LeducMove> {
+        match self.walk(moves) {
+            Betting::ToAct { actions, .. } => actions,
+            _ => unreachable!(),
+        }
+    }
+
+    fn random_event(&self, moves: &[LeducMove]) -> Vec<(LeducMove, f64)> {
+        let suits = f64::from(self.suits);
+
+        match moves {
+            [] => (0..self.ranks)
+                .flat_map(|first| (0..self.ranks).map(move |second| (first, second)))
+                .filter(|(first, second)| self.suits > 1 || first != second)
+                .map(|(first, second)| {
+                    let same = if first == second { 1. } else { 0. };
+                    let p = suits / self.deck() * (suits - same) / (self.deck() - 1.);
+
+                    (LeducMove::Deal { first, second }, p)
+                })
+                .collect(),
+            [LeducMove::Deal { first, second }, ..] => (0..self.ranks)
+                .map(|card| {
+                    let dealt = [first, second].iter().filter(|x| ***x == card).count();
+
+                    (card, suits - dealt as f64)
+                })
+                .filter(|(_, left)| *left > 0.)
+                .map(|(card, left)| (LeducMove::Public(card), left / (self.deck() - 2.)))
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::GameTree;
+
+    #[test]
+    fn private_cards_are_hidden() {
+        let deal = LeducMove::Deal {
+            first: 0,
+            second: 2,
+        };
+
+        assert_eq!(
+            LeducFirstView::from(deal),
+            LeducFirstView::Deal { first: 0 }
+        );
+        assert_eq!(
+            LeducSecondView::from(deal),
+            LeducSecondView::Deal { second: 2 }
+        );
+    }
+
+    #[test]
+    fn standard_game_value() {
+        let solution = GameTree::from_rules(LeducHoldem::default()).solve_sequence_form();
+
+        assert!((solution.value + 0.0856).abs() < 1e-3);
+    }
+
+    #[test]
+    fn larger_decks_sum_up_their_deals() {
+        assert!(GameTree::try_from_rules(LeducHoldem::new(6, 2, [2., 4.], 2)).is_ok());
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        assert!(LeducHoldem::try_new(0, 4, [2., 4.], 1).is_err());
+        assert!(LeducHoldem::try_new(1, 2, [2., 4.], 1).is_err());
+        assert!(LeducHoldem::try_new(3, 2, [0., 4.], 1).is_err());
+        assert!(LeducHoldem::try_new(3, 2, [2., -4.], 1).is_err());
+        assert!(LeducHoldem::try_new(3, 2, [2., f64::NAN], 1).is_err());
+        assert!(LeducHoldem::try_new(1, 3, [2., 4.], 0).is_ok());
+    }
+
+    /// Benchmark of the tree build and the sequence-form solver, run with
+    /// `cargo test --release -- --ignored build_and_solve_time --nocapture`
+    #[test]
+    #[ignore]
+    fn build_and_solve_time() {
+        for rules in [LeducHoldem::default(), LeducHoldem::new(6, 2, [2., 4.], 2)] {
+            let start = std::time::Instant::now();
+            let tree = GameTree::from_rules(rules);
+            let built = start.elapsed();
+            let value = tree.solve_sequence_form().value;
+
+            eprintln!(
+                "{rules:?}: built in {built:?}, solved in {:?}, value {value:.4}",
+                start.elapsed() - built
+            );
+        }
+    }
+}
@@ -0,0 +1,160 @@
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, Observation, State};
+use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+
+/// Normal-form game played as a game tree: the first player picks a row, then the second player
+/// picks a column without seeing the row
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MatrixRules {
+    game: BimatrixGame,
+}
+
+impl MatrixRules {
+    /// Zero-sum game of the first player's payoffs
+    pub fn new(matrix: PayoffMatrix) -> Self {
+        Self::bimatrix(BimatrixGame::zero_sum(matrix))
+    }
+
+    /// Zero-sum game of the first player's payoffs given by rows, labelled by their indices
+    pub fn from_rows(data: Vec<Vec<f64>>) -> Result<Self, MontyError> {
+        Ok(Self::new(PayoffMatrix::new(data)?))
+    }
+
+    pub fn bimatrix(game: BimatrixGame) -> Self {
+        Self { game }
+    }
+
+    pub fn game(&self) -> &BimatrixGame {
+        &self.game
+    }
+
+    /// Label of the row or the column
+    pub fn label(&self, m: MatrixMove) -> &str {
+        match m {
+            MatrixMove::Row(i) => &self.game.first.row_labels()[i],
+            MatrixMove::Column(j) => &self.game.first.col_labels()[j],
+        }
+    }
+}
+
+impl From<PayoffMatrix> for MatrixRules {
+    fn from(value: PayoffMatrix) -> Self {
+        Self::new(value)
+    }
+}
+
+impl From<BimatrixGame> for MatrixRules {
+    fn from(value: BimatrixGame) -> Self {
+        Self::bimatrix(value)
+    }
+}
+
+/// A row is only revealed to the first player and a column to the second one
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash, Observation)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[observation(
+    first = MatrixFirstView,
+    second = MatrixSecondView,
+    attrs(
+        derive(Ord, PartialOrd),
+        cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))
+    )
+)]
+pub enum MatrixMove {
+    #[observation(first)]
+    Row(usize),
+    #[observation(second)]
+    Column(usize),
+}
+
+impl GameRules<MatrixMove, MatrixFirstView, MatrixSecondView> for MatrixRules {
+    fn ask_arbiter(&self, moves: &[MatrixMove]) -> State {
+        match moves {
+            [] => State::FirstToMove,
+            [_] => State::SecondToMove,
+            [MatrixMove::Row(i), MatrixMove::Column(j)] => {
+                State::GameOver([self.game.first[*i][*j], self.game.second[*i][*j]])
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn ask_first(&self, _moves: &[MatrixFirstView]) -> Vec<MatrixMove> {
+        (0..self.game.first.n_rows()).map(MatrixMove::Row).collect()
+    }
+
+    fn ask_second(&self, _moves: &[MatrixSecondView]) -> Vec<MatrixMove> {
+        (0..self.game.first.n_cols())
+            .map(MatrixMove::Column)
+            .collect()
+    }
+
+    fn random_event(&self, _moves: &[MatrixMove]) -> Vec<(MatrixMove, f64)> {
+        unreachable!()
+    }
+
+    fn move_name(&self, m: MatrixMove) -> String {
+        String::from(self.label(m))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::GameTree;
+    use crate::matrix_game::solve_zero_sum;
+
+    fn table() -> PayoffMatrix {
+        PayoffMatrix::new(vec![vec![4., -1., 0.], vec![-2., 3., 1.]])
+            .unwrap()
+            .with_labels(
+                vec![String::from("advertise"), String::from("discount")],
+                vec![
+                    String::from("hold"),
+                    String::from("match"),
+                    String::from("exit"),
+                ],
+            )
+            .unwrap()
+    }
+
+    #[test]
+    fn labels_survive_the_tree() {
+        let tree = GameTree::from_rules(MatrixRules::new(table()));
+        let matrix = tree.to_matrix();
+        let order = |labels: &[String], wanted: &[String]| -> Vec<usize> {
+            wanted
+                .iter()
+                .map(|x| labels.iter().position(|y| y == x).unwrap())
+                .collect()
+        };
+        let rows = order(matrix.row_labels(), table().row_labels());
+        let cols = order(matrix.col_labels(), table().col_labels());
+
+        assert_eq!(matrix.select(&rows, &cols), table());
+        assert!(tree.to_efg().contains("\"advertise\""));
+        assert!(tree.to_json().contains("\"match\""));
+        assert!(tree.to_dot().contains("exit"));
+    }
+
+    #[test]
+    fn sequence_form_matches_the_matrix() {
+        let tree = GameTree::from_rules(MatrixRules::new(table()));
+
+        assert!((tree.solve_sequence_form().value - solve_zero_sum(&table()).value).abs() < 1e-6);
+    }
+
+    #[test]
+    fn bimatrix_games_keep_both_payoffs() {
+        let game = BimatrixGame::new(
+            PayoffMatrix::new(vec![vec![3., 0.], vec![0., 2.]]).unwrap(),
+            PayoffMatrix::new(vec![vec![2., 0.], vec![0., 3.]]).unwrap(),
+        )
+        .unwrap();
+        let tree = GameTree::from_rules(MatrixRules::bimatrix(game));
+
+        assert!(!tree.is_zero_sum());
+        assert_eq!(tree.to_bimatrix().support_enumeration().len(), 3);
+    }
+}
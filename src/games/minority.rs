@@ -0,0 +1,109 @@
+use crate::error::MontyError;
+use crate::game_tree::n_player::{Rules, State};
+
+/// Each of the players secretly picks a side; the players on the less crowded side win
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Minority {
+    players: usize,
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MinorityMove {
+    pub player: usize,
+    pub side: bool,
+}
+
+impl Minority {
+    pub fn new(players: usize) -> Self {
+        Self::try_new(players).unwrap()
+    }
+
+    pub fn try_new(players: usize) -> Result<Self, MontyError> {
+        if players == 0 {
+            return Err(MontyError::BadParameter {
+                name: String::from("players"),
+                message: String::from("the game needs at least one player"),
+            });
+        }
+
+        Ok(Self { players })
+    }
+}
+
+impl Rules<MinorityMove, Option<bool>> for Minority {
+    fn players(&self) -> usize {
+        self.players
+    }
+
+    fn ask_arbiter(&self, moves: &[MinorityMove]) -> State {
+        if moves.len() < self.players {
+            return State::ToMove(moves.len());
+        }
+
+        let ones = moves.iter().filter(|m| m.side).count();
+        let minority = 2 * ones < self.players;
+        let majority = 2 * ones > self.players;
+
+        State::GameOver(
+            moves
+                .iter()
+                .map(|m| {
+                    if (m.side && minority) || (!m.side && majority) {
+                        1.
+                    } else {
+                        0.
+                    }
+                })
+                .collect(),
+        )
+    }
+
+    fn observe(&self, player: usize, event: MinorityMove) -> Option<bool> {
+        if event.player == player {
+            Some(event.side)
+        } else {
+            None
+        }
+    }
+
+    fn ask_player(&self, player: usize, _moves: &[Option<bool>]) -> Vec<MinorityMove> {
+        vec![
+            MinorityMove {
+                player,
+                side: false,
+            },
+            MinorityMove { player, side: true },
+        ]
+    }
+
+    fn random_event(&self, _moves: &[MinorityMove]) -> Vec<(MinorityMove, f64)> {
+        unreachable!()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::n_player::NPlayerTree;
+
+    #[test]
+    fn the_lonely_player_wins() {
+        let tensor = NPlayerTree::from_rules(Minority::new(3)).to_tensor();
+
+        assert_eq!(tensor.shape, vec![2, 2, 2]);
+        assert_eq!(
+            (0..3)
+                .map(|k| tensor.payoff(k, &[0, 0, 1]))
+                .collect::<Vec<_>>(),
+            vec![0., 0., 1.]
+        );
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        assert!(Minority::try_new(0).is_err());
+        assert!(Minority::try_new(1).is_ok());
+    }
+}
@@ -0,0 +1,325 @@
+use crate::error::MontyError;
+use crate::game_tree::rules::{GameRules, State};
+use std::fmt::{Debug, Formatter};
+
+/// How the host chooses which doors to open
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Host {
+    /// Always opens doors hiding nothing among the ones the contestant did not pick
+    #[default]
+    Standard,
+    /// Opens random doors among the ones the contestant did not pick, possibly revealing the prize
+    /// (Monty Fall); the prize is lost in that case
+    Ignorant,
+    /// Offers a switch only when the contestant's first pick is wrong
+    Angelic,
+    /// Offers a switch only when the contestant's first pick is right
+    Evil,
+}
+
+/// Who places the prize and chooses among the doors the host may open
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum Placement {
+    /// Uniformly at random
+    #[default]
+    Random,
+    /// The host is the second player and plays against the contestant
+    Host,
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MontyHall {
+    doors: u8,
+    opened: u8,
+    host: Host,
+    placement: Placement,
+}
+
+impl Default for MontyHall {
+    fn default() -> Self {
+        Self::new(3, 1, Host::Standard, Placement::Random)
+    }
+}
+
+impl MontyHall {
+    pub fn new(doors: u8, opened: u8, host: Host, placement: Placement) -> Self {
+        Self::try_new(doors, opened, host, placement).unwrap()
+    }
+
+    pub fn try_new(
+        doors: u8,
+        opened: u8,
+        host: Host,
+        placement: Placement,
+    ) -> Result<Self, MontyError> {
+        if doors > 32 {
+            return Err(MontyError::BadParameter {
+                name: String::from("doors"),
+                message: String::from("at most 32 doors are supported"),
+            });
+        }
+        if doors < opened.saturating_add(2) {
+            return Err(MontyError::BadParameter {
+                name: String::from("opened"),
+                message: String::from("the host must leave at least two doors closed"),
+            });
+        }
+
+        Ok(Self {
+            doors,
+            opened,
+            host,
+            placement,
+        })
+    }
+
+    /// All sets of `self.opened` doors (as bitmasks) that avoid every door in `forbidden`
+    fn door_sets(&self, forbidden: u32) -> Vec<u32> {
+        let mut ans = vec![];
+        let mut stack = vec![(0u8, 0u32, 0u8)];
+
+        while let Some((door, mask, count)) = stack.pop() {
+            if count == self.opened {
+                ans.push(mask);
+            } else if door < self.doors {
+                stack.push((door + 1, mask, count));
+                if forbidden & (1 << door) == 0 {
+                    stack.push((door + 1, mask | (1 << door), count + 1));
+                }
+            }
+        }
+
+        ans
+    }
+
+    fn doors_to_open(&self, prize: u8, pick: u8) -> Vec<u32> {
+        match self.host {
+            Host::Ignorant => self.door_sets(1 << pick),
+            _ => self.door_sets((1 << pick) | (1 << prize)),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MontyMove {
+    Prize(u8),
+    Pick(u8),
+    Open(u32),
+    Final(u8),
+}
+
+impl MontyMove {
+    fn door(self) -> u8 {
+        match self {
+            MontyMove::Prize(x) | MontyMove::Pick(x) | MontyMove::Final(x) => x,
+            MontyMove::Open(_) => unreachable!(),
+        }
+    }
+
+    fn mask(self) -> u32 {
+        match self {
+            MontyMove::Open(x) => x,
+            _ => unreachable!(),
+        }
+    }
+}
+
+/// Doors of a bitmask, numbered from one as the contestant would
+fn door_names(mask: u32) -> String {
+    let doors: Vec<String> = (0..32)
+        .filter(|door| mask & (1 << door) != 0)
+        .map(|door| (door + 1).to_string())
+        .collect();
+
+    match &doors[..] {
+        [door] => format!("door {door}"),
+        _ => format!("doors {}", doors.join(", ")),
+    }
+}
+
+/// What the contestant sees: everything but the prize location
+#[derive(Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum ContestantView {
+    #[default]
+    Hidden,
+    Pick(u8),
+    Open(u32),
+    Final(u8),
+}
+
+/// Histories of the contestant name their information sets in the strategy labels, so they read
+/// as the moves do
+impl Debug for ContestantView {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ContestantView::Hidden => write!(f, "hidden prize"),
+            ContestantView::Pick(x) => write!(f, "picked {}", door_names(1 << x)),
+            ContestantView::Open(x) => write!(f, "opened {}", door_names(*x)),
+            ContestantView::Final(x) => write!(f, "chose {}", door_names(1 << x)),
+        }
+    }
+}
+
+impl From<MontyMove> for ContestantView {
+    fn from(value: MontyMove) -> Self {
+        match value {
+            MontyMove::Prize(_) => Self::Hidden,
+            MontyMove::Pick(x) => Self::Pick(x),
+            MontyMove::Open(x) => Self::Open(x),
+            MontyMove::Final(x) => Self::Final(x),
+        }
+    }
+}
+
+impl GameRules<MontyMove, ContestantView, MontyMove> for MontyHall {
+    fn ask_arbiter(&self, moves: &[MontyMove]) -> State {
+        match moves.len() {
+            0 => match self.placement {
+                Placement::Random => State::RandomEvent,
+                Placement::Host => State::SecondToMove,
+            },
+            1 => State::FirstToMove,
+            2 => {
+                let right = moves[0].door() == moves[1].door();
+
+                match (self.host, self.placement) {
+                    (Host::Angelic, _) if right => State::zero_sum(1.),
+                    (Host::Evil, _) if !right => State::zero_sum(0.),
+                    (Host::Ignorant, _) | (_, Placement::Random) => State::RandomEvent,
+                    (_, Placement::Host) => State::SecondToMove,
+                }
+            }
+            3 => State::FirstToMove,
+            4 => {
+                let prize = moves[0].door();
+
+                if moves[2].mask() & (1 << prize) != 0 || moves[3].door() != prize {
+                    State::zero_sum(0.)
+                } else {
+                    State::zero_sum(1.)
+                }
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn ask_first(&self, moves: &[ContestantView]) -> Vec<MontyMove> {
+        match moves {
+            [_] => (0..self.doors).map(MontyMove::Pick).collect(),
+            [_, _, ContestantView::Open(mask)] => (0..self.doors)
+                .filter(|door| mask & (1 << door) == 0)
+                .map(MontyMove::Final)
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn ask_second(&self, moves: &[MontyMove]) -> Vec<MontyMove> {
+        match moves {
+            [] => (0..self.doors).map(MontyMove::Prize).collect(),
+            [prize, pick] => self
+                .doors_to_open(prize.door(), pick.door())
+                .into_iter()
+                .map(MontyMove::Open)
+                .collect(),
+            _ => unreachable!(),
+        }
+    }
+
+    fn random_event(&self, moves: &[MontyMove]) -> Vec<(MontyMove, f64)> {
+        match moves {
+            [] => (0..self.doors)
+                .map(|door| (MontyMove::Prize(door), 1. / self.doors as f64))
+                .collect(),
+            [prize, pick] => {
+                let sets = self.doors_to_open(prize.door(), pick.door());
+                let p = 1. / sets.len() as f64;
+
+                sets.into_iter()
+                    .map(|mask| (MontyMove::Open(mask), p))
+                    .collect()
+            }
+            _ => unreachable!(),
+        }
+    }
+
+    fn move_name(&self, m: MontyMove) -> String {
+        match m {
+            MontyMove::Prize(x) => format!("prize behind {}", door_names(1 << x)),
+            MontyMove::Pick(x) => format!("pick {}", door_names(1 << x)),
+            MontyMove::Open(x) => format!("open {}", door_names(x)),
+            MontyMove::Final(x) => format!("choose {}", door_names(1 << x)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::GameTree;
+    use crate::matrix_game::solve_zero_sum;
+
+    fn value(rules: MontyHall) -> f64 {
+        solve_zero_sum(&GameTree::from_rules(rules).to_matrix()).value
+    }
+
+    #[test]
+    fn switching_wins_two_thirds() {
+        for placement in [Placement::Random, Placement::Host] {
+            let rules = MontyHall::new(3, 1, Host::Standard, placement);
+
+            assert!((value(rules) - 2. / 3.).abs() < 1e-6);
+        }
+    }
+
+    #[test]
+    fn hosts() {
+        let value = |host| value(MontyHall::new(3, 1, host, Placement::Random));
+
+        // The prize is lost when the ignorant host reveals it
+        assert!((value(Host::Ignorant) - 1. / 3.).abs() < 1e-6);
+        assert!((value(Host::Angelic) - 1.).abs() < 1e-6);
+        assert!((value(Host::Evil) - 1. / 3.).abs() < 1e-6);
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        assert!(MontyHall::try_new(33, 1, Host::Standard, Placement::Random).is_err());
+        assert!(MontyHall::try_new(3, 2, Host::Standard, Placement::Random).is_err());
+        assert!(MontyHall::try_new(3, 255, Host::Standard, Placement::Random).is_err());
+        assert!(MontyHall::try_new(4, 2, Host::Standard, Placement::Random).is_ok());
+    }
+
+    #[test]
+    fn readable_strategies() {
+        let rules = MontyHall::new(4, 2, Host::Standard, Placement::Random);
+
+        assert_eq!(rules.move_name(MontyMove::Open(0b1010)), "open doors 2, 4");
+        assert_eq!(rules.move_name(MontyMove::Final(0)), "choose door 1");
+        assert!(GameTree::from_rules(rules)
+            .to_matrix()
+            .row_labels()
+            .iter()
+            .any(|label| label.starts_with(
+                "[hidden prize] -> pick door 1; [hidden prize, picked door 1, opened doors 2, 3] \
+                 -> choose door 4"
+            )));
+    }
+
+    #[test]
+    fn four_doors() {
+        let value = |host| {
+            GameTree::from_rules(MontyHall::new(4, 2, host, Placement::Host))
+                .solve_sequence_form()
+                .value
+        };
+
+        assert!((value(Host::Standard) - 0.75).abs() < 1e-6);
+        assert!((value(Host::Angelic) - 1.).abs() < 1e-6);
+    }
+}
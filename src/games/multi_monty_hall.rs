@@ -0,0 +1,199 @@
+use crate::error::MontyError;
+use crate::game_tree::n_player::{Rules, State};
+
+/// Monty Hall with several contestants: the prize is placed at random, every contestant secretly
+/// picks a door, the host opens a random door that hides nothing and nobody picked, then every
+/// contestant makes a final choice among the closed doors; whoever ends on the prize wins it
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct MultiMontyHall {
+    contestants: usize,
+    doors: u8,
+}
+
+impl MultiMontyHall {
+    pub fn new(contestants: usize, doors: u8) -> Self {
+        Self::try_new(contestants, doors).unwrap()
+    }
+
+    pub fn try_new(contestants: usize, doors: u8) -> Result<Self, MontyError> {
+        if contestants == 0 {
+            return Err(MontyError::BadParameter {
+                name: String::from("contestants"),
+                message: String::from("the game needs at least one contestant"),
+            });
+        }
+        if (doors as usize) < contestants + 2 {
+            return Err(MontyError::BadParameter {
+                name: String::from("doors"),
+                message: format!("the host needs a door besides the prize and {contestants} picks"),
+            });
+        }
+
+        Ok(Self { contestants, doors })
+    }
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MultiMontyMove {
+    Prize(u8),
+    /// First pick of a contestant
+    Pick(usize, u8),
+    Open(u8),
+    /// Final choice of a contestant
+    Final(usize, u8),
+}
+
+/// What a contestant sees: their own picks and the opened door
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum MultiMontyView {
+    #[default]
+    Hidden,
+    Pick(u8),
+    Open(u8),
+    Final(u8),
+}
+
+impl Rules<MultiMontyMove, MultiMontyView> for MultiMontyHall {
+    fn players(&self) -> usize {
+        self.contestants
+    }
+
+    fn ask_arbiter(&self, moves: &[MultiMontyMove]) -> State {
+        let n = self.contestants;
+
+        match moves.len() {
+            0 => State::RandomEvent,
+            k if k <= n => State::ToMove(k - 1),
+            k if k == n + 1 => State::RandomEvent,
+            k if k <= 2 * n + 1 => State::ToMove(k - n - 2),
+            _ => {
+                let MultiMontyMove::Prize(prize) = moves[0] else {
+                    unreachable!()
+                };
+
+                State::GameOver(
+                    moves[n + 2..]
+                        .iter()
+                        .map(|m| match m {
+                            MultiMontyMove::Final(_, door) if *door == prize => 1.,
+                            _ => 0.,
+                        })
+                        .collect(),
+                )
+            }
+        }
+    }
+
+    fn observe(&self, player: usize, event: MultiMontyMove) -> MultiMontyView {
+        match event {
+            MultiMontyMove::Pick(k, door) if k == player => MultiMontyView::Pick(door),
+            MultiMontyMove::Open(door) => MultiMontyView::Open(door),
+            MultiMontyMove::Final(k, door) if k == player => MultiMontyView::Final(door),
+            _ => MultiMontyView::Hidden,
+        }
+    }
+
+    fn ask_player(&self, player: usize, moves: &[MultiMontyView]) -> Vec<MultiMontyMove> {
+        let opened = moves.iter().find_map(|m| match m {
+            MultiMontyView::Open(door) => Some(*door),
+            _ => None,
+        });
+
+        match opened {
+            None => (0..self.doors)
+                .map(|door| MultiMontyMove::Pick(player, door))
+                .collect(),
+            Some(opened) => (0..self.doors)
+                .filter(|door| *door != opened)
+                .map(|door| MultiMontyMove::Final(player, door))
+                .collect(),
+        }
+    }
+
+    fn random_event(&self, moves: &[MultiMontyMove]) -> Vec<(MultiMontyMove, f64)> {
+        if moves.is_empty() {
+            return (0..self.doors)
+                .map(|door| (MultiMontyMove::Prize(door), 1. / self.doors as f64))
+                .collect();
+        }
+
+        let closed: Vec<u8> = (0..self.doors)
+            .filter(|door| {
+                moves.iter().all(|m| match m {
+                    MultiMontyMove::Prize(x) | MultiMontyMove::Pick(_, x) => x != door,
+                    _ => true,
+                })
+            })
+            .collect();
+        let p = 1. / closed.len() as f64;
+
+        closed
+            .into_iter()
+            .map(|door| (MultiMontyMove::Open(door), p))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::n_player::{NPlayerTree, PlayerStrategy};
+
+    /// Picks door 0, then keeps it or takes the first other closed door
+    struct Contestant {
+        switch: bool,
+    }
+
+    impl PlayerStrategy<MultiMontyMove, MultiMontyView> for Contestant {
+        fn make_move(
+            &self,
+            player: usize,
+            play: &[MultiMontyView],
+        ) -> Result<MultiMontyMove, MontyError> {
+            match play.iter().find_map(|m| match m {
+                MultiMontyView::Open(door) => Some(*door),
+                _ => None,
+            }) {
+                None => Ok(MultiMontyMove::Pick(player, 0)),
+                Some(_) if !self.switch => Ok(MultiMontyMove::Final(player, 0)),
+                Some(opened) => Ok(MultiMontyMove::Final(
+                    player,
+                    (1..).find(|door| *door != opened).unwrap(),
+                )),
+            }
+        }
+    }
+
+    const STAY: Contestant = Contestant { switch: false };
+    const SWITCH: Contestant = Contestant { switch: true };
+
+    #[test]
+    fn one_contestant_is_the_classic_game() {
+        let tree = NPlayerTree::from_rules(MultiMontyHall::new(1, 3));
+
+        assert!((tree.simulate(&[&STAY])[0] - 1. / 3.).abs() < 1e-9);
+        assert!((tree.simulate(&[&SWITCH])[0] - 2. / 3.).abs() < 1e-9);
+        // A door for the first pick and a final door for each of the two doors the host may open
+        assert_eq!(tree.to_tensor().shape, vec![3 * 2 * 2]);
+    }
+
+    #[test]
+    fn switching_still_pays_with_a_rival() {
+        let tree = NPlayerTree::from_rules(MultiMontyHall::new(2, 4));
+        let stay = tree.simulate(&[&STAY, &STAY]);
+        let switch = tree.simulate(&[&SWITCH, &STAY]);
+
+        assert!((stay[0] - 0.25).abs() < 1e-9 && (stay[1] - 0.25).abs() < 1e-9);
+        assert!((switch[0] - 0.375).abs() < 1e-9 && (switch[1] - 0.25).abs() < 1e-9);
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        assert!(MultiMontyHall::try_new(0, 3).is_err());
+        assert!(MultiMontyHall::try_new(2, 3).is_err());
+        assert!(MultiMontyHall::try_new(2, 4).is_ok());
+    }
+}
@@ -0,0 +1,8 @@
+pub mod error;
+pub mod gambit;
+pub mod game_tree;
+pub mod games;
+pub mod matrix_game;
+pub mod registry;
+#[cfg(feature = "serde")]
+pub mod serialization;
@@ -1,30 +1,465 @@
-use crate::game_tree::GameTree;
-use crate::matrix_game::{reverse_game, solve_game};
-use games::{Guess, RockPaperScissors, TwistedRockPaperScissors};
+use monty_hall::error::MontyError;
+use monty_hall::game_tree::dyn_game::DynGame;
+use monty_hall::game_tree::interactive::Seat;
+use monty_hall::matrix_game::{try_solve_zero_sum, Equilibrium, PayoffMatrix};
+use monty_hall::registry::{ParamKind, Registry};
+use std::fmt::Display;
+use std::process::ExitCode;
+use std::str::FromStr;
 
-mod game_tree;
-mod games;
-mod matrix_game;
+const USAGE: &str = "usage:
+    monty-hall list
+    monty-hall solve <game> [--param name=value]... [--format table|json|csv]
+    monty-hall matrix <game> [--param name=value]... [--format table|json|csv]
+    monty-hall tree <game> [--param name=value]... [--format dot|json|efg]
+    monty-hall play <game> [--param name=value]... [--seat first|second] [--rounds n]";
 
-fn main() {
-    let game = GameTree::from_rules(Guess::default()).to_matrix();
+enum CliError {
+    /// Malformed command line, exit code 2
+    Usage(String),
+    /// The game cannot be built or solved, exit code 1
+    Failed(MontyError),
+}
+
+impl From<MontyError> for CliError {
+    fn from(value: MontyError) -> Self {
+        match value {
+            MontyError::UnknownGame(_) | MontyError::BadParameter { .. } => {
+                CliError::Usage(value.to_string())
+            }
+            _ => CliError::Failed(value),
+        }
+    }
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+enum Format {
+    Table,
+    Json,
+    Csv,
+    Dot,
+    Efg,
+}
+
+impl FromStr for Format {
+    type Err = CliError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "table" => Ok(Format::Table),
+            "json" => Ok(Format::Json),
+            "csv" => Ok(Format::Csv),
+            "dot" => Ok(Format::Dot),
+            "efg" => Ok(Format::Efg),
+            _ => Err(CliError::Usage(format!("unknown format {s}"))),
+        }
+    }
+}
+
+struct Options {
+    command: String,
+    game: Option<String>,
+    params: Vec<(String, String)>,
+    format: Option<Format>,
+    seat: Seat,
+    rounds: usize,
+}
+
+impl Options {
+    fn parse(mut args: impl Iterator<Item = String>) -> Result<Self, CliError> {
+        let command = args
+            .next()
+            .ok_or_else(|| CliError::Usage(String::from("no command")))?;
+        let mut game = None;
+        let mut params = vec![];
+        let mut format = None;
+        let mut seat = Seat::First;
+        let mut rounds = usize::MAX;
+
+        while let Some(arg) = args.next() {
+            let mut value = || {
+                args.next()
+                    .ok_or_else(|| CliError::Usage(format!("{arg} needs a value")))
+            };
+
+            match arg.as_str() {
+                "--param" => {
+                    let param = value()?;
+                    let (name, x) = param
+                        .split_once('=')
+                        .ok_or_else(|| CliError::Usage(format!("expected name=value: {param}")))?;
+                    params.push((String::from(name), String::from(x)));
+                }
+                "--format" => format = Some(value()?.parse()?),
+                "--seat" => {
+                    seat = match value()?.as_str() {
+                        "first" => Seat::First,
+                        "second" => Seat::Second,
+                        x => return Err(CliError::Usage(format!("unknown seat {x}"))),
+                    }
+                }
+                "--rounds" => {
+                    let x = value()?;
+                    rounds = x
+                        .parse()
+                        .map_err(|_| CliError::Usage(format!("bad number of rounds {x}")))?;
+                }
+                _ if arg.starts_with("--") => {
+                    return Err(CliError::Usage(format!("unknown option {arg}")))
+                }
+                _ if game.is_none() => game = Some(arg),
+                _ => return Err(CliError::Usage(format!("unexpected argument {arg}"))),
+            }
+        }
+
+        Ok(Self {
+            command,
+            game,
+            params,
+            format,
+            seat,
+            rounds,
+        })
+    }
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        String::from(s)
+    }
+}
+
+fn json_string(s: &str) -> String {
+    let mut ans = String::from("\"");
+
+    for c in s.chars() {
+        match c {
+            '"' => ans.push_str("\\\""),
+            '\\' => ans.push_str("\\\\"),
+            '\n' => ans.push_str("\\n"),
+            c if c.is_control() => ans.push_str(&format!("\\u{:04x}", c as u32)),
+            c => ans.push(c),
+        }
+    }
+
+    ans.push('"');
+    ans
+}
+
+/// JSON has no infinities and NaNs, which become `null`
+fn json_number(x: f64) -> String {
+    if x.is_finite() {
+        format!("{}", x + 0.)
+    } else {
+        String::from("null")
+    }
+}
+
+fn json_list<T>(items: &[T], f: impl Fn(&T) -> String) -> String {
+    format!("[{}]", items.iter().map(f).collect::<Vec<_>>().join(","))
+}
+
+fn matrix(game: &PayoffMatrix, format: Format) -> Result<String, CliError> {
+    match format {
+        Format::Table => Ok(game.to_string()),
+        Format::Json => Ok(format!(
+            "{{\"rows\":{},\"cols\":{},\"payoffs\":{}}}\n",
+            json_list(game.row_labels(), |s| json_string(s)),
+            json_list(game.col_labels(), |s| json_string(s)),
+            json_list(&game.iter().collect::<Vec<_>>(), |row| json_list(
+                row,
+                |x| json_number(*x)
+            ))
+        )),
+        Format::Csv => {
+            let mut ans = String::new();
+            for label in game.col_labels() {
+                ans += &format!(",{}", csv_field(label));
+            }
+            ans.push('\n');
+            for (label, row) in game.row_labels().iter().zip(game) {
+                ans += &csv_field(label);
+                for x in row {
+                    ans += &format!(",{x}");
+                }
+                ans.push('\n');
+            }
+            Ok(ans)
+        }
+        _ => Err(CliError::Usage(format!(
+            "{format:?} is not a matrix format"
+        ))),
+    }
+}
+
+fn solve(
+    game: &PayoffMatrix,
+    equilibrium: &Equilibrium,
+    format: Format,
+) -> Result<String, CliError> {
+    // Strategies played with a positive probability
+    let support = |labels: &[String], distribution: &[f64]| -> Vec<(String, f64)> {
+        labels
+            .iter()
+            .cloned()
+            .zip(distribution.iter().copied())
+            .filter(|(_, p)| *p > 1e-9)
+            .collect()
+    };
+    let first = support(game.row_labels(), &equilibrium.first);
+    let second = support(game.col_labels(), &equilibrium.second);
+
+    match format {
+        Format::Table => {
+            let mut ans = format!("value: {:.6}\n", equilibrium.value);
+            for (player, strategies) in [("first", &first), ("second", &second)] {
+                ans += &format!("{player} player:\n");
+                for (label, p) in strategies {
+                    ans += &format!("  {p:.6}  {label}\n");
+                }
+            }
+            Ok(ans)
+        }
+        Format::Json => {
+            let strategies = |s: &Vec<(String, f64)>| {
+                json_list(s, |(label, p)| {
+                    format!(
+                        "{{\"strategy\":{},\"probability\":{}}}",
+                        json_string(label),
+                        json_number(*p)
+                    )
+                })
+            };
+            Ok(format!(
+                "{{\"value\":{},\"first\":{},\"second\":{}}}\n",
+                json_number(equilibrium.value),
+                strategies(&first),
+                strategies(&second)
+            ))
+        }
+        Format::Csv => {
+            let mut ans = format!(
+                "player,strategy,probability\nvalue,,{}\n",
+                equilibrium.value
+            );
+            for (player, strategies) in [("first", &first), ("second", &second)] {
+                for (label, p) in strategies {
+                    ans += &format!("{player},{},{p}\n", csv_field(label));
+                }
+            }
+            Ok(ans)
+        }
+        _ => Err(CliError::Usage(format!(
+            "{format:?} is not a solution format"
+        ))),
+    }
+}
+
+fn run(game: &dyn DynGame, options: &Options) -> Result<String, CliError> {
+    match options.command.as_str() {
+        "solve" => {
+            if !game.is_zero_sum() {
+                return Err(CliError::Failed(MontyError::NotZeroSum));
+            }
+
+            let matrix = game.try_to_matrix()?;
+
+            solve(
+                &matrix,
+                &try_solve_zero_sum(&matrix)?,
+                options.format.unwrap_or(Format::Table),
+            )
+        }
+        "matrix" => matrix(
+            &game.try_to_matrix()?,
+            options.format.unwrap_or(Format::Table),
+        ),
+        "tree" => match options.format.unwrap_or(Format::Dot) {
+            Format::Dot => Ok(game.to_dot()),
+            Format::Json => Ok(game.to_json() + "\n"),
+            Format::Efg => Ok(game.to_efg()),
+            format => Err(CliError::Usage(format!("{format:?} is not a tree format"))),
+        },
+        "play" => {
+            let stdin = std::io::stdin();
+            let average = game.try_play(
+                options.seat,
+                options.rounds,
+                &mut stdin.lock(),
+                &mut std::io::stdout(),
+            )?;
+
+            Ok(match average {
+                None => String::from("no games played\n"),
+                Some(x) => format!("average payoff: {x:.4}\n"),
+            })
+        }
+        command => Err(CliError::Usage(format!("unknown command {command}"))),
+    }
+}
+
+fn list(registry: &Registry) -> String {
+    let mut ans = String::new();
+
+    for game in registry.games() {
+        ans += &format!("{}: {}\n", game.name, game.description);
+        for param in &game.params {
+            let values = match param.kind {
+                ParamKind::Float => String::from("number"),
+                ParamKind::Integer => String::from("integer"),
+                ParamKind::Choice(names) => names.join("|"),
+            };
+            ans += &format!(
+                "    --param {}={} ({values}): {}\n",
+                param.name, param.default, param.description
+            );
+        }
+    }
+
+    ans
+}
+
+fn execute(options: &Options) -> Result<String, CliError> {
+    let registry = Registry::with_builtin_games();
+
+    if options.command == "list" {
+        return Ok(list(&registry));
+    }
+
+    let name = options
+        .game
+        .as_deref()
+        .ok_or_else(|| CliError::Usage(String::from("no game")))?;
+    let params: Vec<(&str, &str)> = options
+        .params
+        .iter()
+        .map(|(name, value)| (name.as_str(), value.as_str()))
+        .collect();
+    let game = registry.build(name, &params)?;
+
+    run(game.as_ref(), options)
+}
+
+fn fail(message: impl Display, code: u8) -> ExitCode {
+    eprintln!("error: {message}");
+    ExitCode::from(code)
+}
+
+fn main() -> ExitCode {
+    let options = match Options::parse(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(CliError::Usage(message)) => return fail(format!("{message}\n{USAGE}"), 2),
+        Err(CliError::Failed(error)) => return fail(error, 1),
+    };
+
+    match execute(&options) {
+        Ok(output) => {
+            print!("{output}");
+            ExitCode::SUCCESS
+        }
+        Err(CliError::Usage(message)) => fail(format!("{message}\n{USAGE}"), 2),
+        Err(CliError::Failed(error)) => fail(error, 1),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    dbg!(&game);
+    fn parse(args: &[&str]) -> Result<Options, CliError> {
+        Options::parse(args.iter().map(|x| String::from(*x)))
+    }
 
-    dbg!(solve_game(&game));
-    dbg!(solve_game(&reverse_game(&game)));
+    fn usage(args: &[&str]) -> String {
+        match parse(args) {
+            Err(CliError::Usage(message)) => message,
+            _ => panic!("{args:?} is not a usage error"),
+        }
+    }
 
-    let game = GameTree::from_rules(RockPaperScissors::default()).to_matrix();
+    #[test]
+    fn parses_options() {
+        let options = parse(&[
+            "play",
+            "monty-hall",
+            "--param",
+            "doors=4",
+            "--param",
+            "host=evil",
+            "--seat",
+            "second",
+            "--rounds",
+            "3",
+        ])
+        .ok()
+        .unwrap();
 
-    dbg!(&game);
+        assert_eq!(options.command, "play");
+        assert_eq!(options.game.as_deref(), Some("monty-hall"));
+        assert_eq!(
+            options.params,
+            vec![
+                (String::from("doors"), String::from("4")),
+                (String::from("host"), String::from("evil"))
+            ]
+        );
+        assert_eq!(options.format, None);
+        assert_eq!(options.seat, Seat::Second);
+        assert_eq!(options.rounds, 3);
+    }
 
-    dbg!(solve_game(&game));
-    dbg!(solve_game(&reverse_game(&game)));
+    #[test]
+    fn defaults() {
+        let options = parse(&["solve", "rps", "--format", "csv"]).ok().unwrap();
 
-    let game = GameTree::from_rules(TwistedRockPaperScissors::new(2.)).to_matrix();
+        assert_eq!(options.format, Some(Format::Csv));
+        assert_eq!(options.seat, Seat::First);
+        assert_eq!(options.rounds, usize::MAX);
+        assert!(options.params.is_empty());
+    }
 
-    dbg!(&game);
+    #[test]
+    fn rejects_malformed_command_lines() {
+        assert_eq!(usage(&[]), "no command");
+        assert_eq!(
+            usage(&["solve", "rps", "--format"]),
+            "--format needs a value"
+        );
+        assert_eq!(
+            usage(&["solve", "rps", "--format", "xml"]),
+            "unknown format xml"
+        );
+        assert_eq!(
+            usage(&["solve", "rps", "--param", "twist"]),
+            "expected name=value: twist"
+        );
+        assert_eq!(
+            usage(&["play", "rps", "--seat", "third"]),
+            "unknown seat third"
+        );
+        assert_eq!(
+            usage(&["play", "rps", "--rounds", "-1"]),
+            "bad number of rounds -1"
+        );
+        assert_eq!(
+            usage(&["solve", "rps", "--quiet"]),
+            "unknown option --quiet"
+        );
+        assert_eq!(
+            usage(&["solve", "rps", "guess"]),
+            "unexpected argument guess"
+        );
+    }
 
-    dbg!(solve_game(&game));
-    dbg!(solve_game(&reverse_game(&game)));
+    #[test]
+    fn literals() {
+        assert_eq!(json_string("a \"b\"\n\\"), r#""a \"b\"\n\\""#);
+        assert_eq!(json_number(-0.), "0");
+        assert_eq!(json_number(f64::INFINITY), "null");
+        assert_eq!(csv_field("a,b"), "\"a,b\"");
+        assert_eq!(csv_field("say \"hi\""), "\"say \"\"hi\"\"\"");
+        assert_eq!(csv_field("plain"), "plain");
+    }
 }
@@ -1,53 +1,185 @@
-use good_lp::{clarabel, variable, variables, Expression, Solution, SolverModel, Variable};
+use crate::error::MontyError;
+use float_cmp::approx_eq;
+use good_lp::solvers::DualValues;
+use good_lp::{
+    clarabel, variable, variables, Expression, Solution, SolutionWithDual, SolverModel, Variable,
+};
+
+mod bimatrix;
+mod dominance;
+mod payoff_matrix;
+
+pub use bimatrix::{BimatrixEquilibrium, BimatrixGame};
+pub use dominance::{
+    eliminate_dominated, try_eliminate_dominated, DominanceKind, Elimination, EliminationStep, Line,
+};
+pub use payoff_matrix::PayoffMatrix;
+
+/// Groups of indices of rows or columns
+pub type Groups = Vec<Vec<usize>>;
 
 #[derive(Debug, Default, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GameSolution {
     cost: f64,
     distribution: Vec<f64>,
 }
 
+/// Optimal mixed strategies of both players of a zero-sum matrix game
+#[derive(Debug, Default, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Equilibrium {
+    /// Value of the game for the rows' player
+    pub value: f64,
+    /// Distribution over the rows
+    pub first: Vec<f64>,
+    /// Distribution over the columns
+    pub second: Vec<f64>,
+}
+
 fn row_expression(variables: &[Variable], row: &[f64]) -> Expression {
     variables.iter().zip(row).map(|(x, c)| *c * *x).sum()
 }
 
-pub fn reverse_game(game: &Vec<Vec<f64>>) -> Vec<Vec<f64>> {
-    let mut ans =
-        vec![vec![0.; game.len()]; game.iter().map(|row| row.len()).max().unwrap_or_default()];
+pub fn reverse_game(game: &PayoffMatrix) -> PayoffMatrix {
+    game.reverse()
+}
+
+fn same_row(a: &[f64], b: &[f64]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).all(|(x, y)| approx_eq!(f64, *x, *y))
+}
+
+/// Groups the indices of identical rows in the order of their first occurrence
+fn duplicate_rows(game: &PayoffMatrix) -> Groups {
+    let mut groups: Groups = vec![];
 
     for (i, row) in game.iter().enumerate() {
-        for (j, val) in row.iter().enumerate() {
-            ans[j][i] = -val;
+        match groups.iter_mut().find(|g| same_row(&game[g[0]], row)) {
+            None => groups.push(vec![i]),
+            Some(g) => g.push(i),
         }
     }
 
-    ans
+    groups
+}
+
+/// Merges identical rows and identical columns, returning the smaller matrix together with the
+/// groups of original rows and columns every new row and column stands for
+///
+/// Every merged row and column keeps the label of its first occurrence
+pub fn merge_duplicates(game: &PayoffMatrix) -> (PayoffMatrix, Groups, Groups) {
+    let rows = duplicate_rows(game);
+    let cols = duplicate_rows(&reverse_game(game));
+
+    let matrix = game.select(
+        &rows.iter().map(|r| r[0]).collect::<Vec<_>>(),
+        &cols.iter().map(|c| c[0]).collect::<Vec<_>>(),
+    );
+
+    (matrix, rows, cols)
 }
 
 /// Solves the matrix game for the columns' player, i.e., the second player
-pub fn solve_game(game: &Vec<Vec<f64>>) -> GameSolution {
+pub fn solve_game(game: &PayoffMatrix) -> GameSolution {
+    try_solve_game(game).unwrap()
+}
+
+pub fn try_solve_game(game: &PayoffMatrix) -> Result<GameSolution, MontyError> {
+    let Equilibrium { value, second, .. } = try_solve_zero_sum(game)?;
+
+    Ok(GameSolution {
+        cost: value,
+        distribution: second,
+    })
+}
+
+/// Solves the matrix game for both players at once: the rows' player strategy is read from the
+/// dual of the columns' player linear program
+pub fn solve_zero_sum(game: &PayoffMatrix) -> Equilibrium {
+    try_solve_zero_sum(game).unwrap()
+}
+
+pub fn try_solve_zero_sum(game: &PayoffMatrix) -> Result<Equilibrium, MontyError> {
     if game.is_empty() {
-        return Default::default();
+        return Ok(Default::default());
     }
 
     variables! {problem: cost;}
-    let cols: Vec<Variable> = problem.add_vector(
-        variable().bounds(0..=1),
-        game.iter().map(|row| row.len()).max().unwrap(),
-    );
+    let cols: Vec<Variable> = problem.add_vector(variable().bounds(0..=1), game.n_cols());
     let total_prob: Expression = cols.iter().sum();
     let mut model = problem
         .minimise(cost)
         .using(clarabel)
         .with(total_prob.eq(1.));
 
-    for row in game {
-        model = model.with(row_expression(&cols, row).leq(cost));
+    let rows: Vec<_> = game
+        .iter()
+        .map(|row| model.add_constraint(row_expression(&cols, row).leq(cost)))
+        .collect();
+
+    let mut solution = model.solve()?;
+
+    let value = solution.value(cost);
+    let second: Vec<f64> = cols.iter().map(|col| solution.value(*col)).collect();
+    let dual = solution.compute_dual();
+    let first: Vec<f64> = rows.into_iter().map(|row| dual.dual(row).abs()).collect();
+
+    let guaranteed = (0..second.len())
+        .map(|j| {
+            game.iter()
+                .zip(&first)
+                .map(|(row, p)| row[j] * p)
+                .sum::<f64>()
+        })
+        .fold(f64::INFINITY, f64::min);
+
+    if !approx_eq!(f64, value, guaranteed, epsilon = 1e-6) {
+        return Err(MontyError::Solver(format!(
+            "primal value {value} differs from dual value {guaranteed}"
+        )));
     }
 
-    let solution = model.solve().unwrap();
+    Ok(Equilibrium {
+        value,
+        first,
+        second,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn both_players_from_one_program() {
+        let game = PayoffMatrix::new(vec![vec![2., -1.], vec![-3., 4.], vec![0., 0.]]).unwrap();
+        let equilibrium = solve_zero_sum(&game);
+        let guaranteed = |j: usize| -> f64 {
+            game.iter()
+                .zip(&equilibrium.first)
+                .map(|(row, p)| row[j] * p)
+                .sum()
+        };
+        let conceded = |i: usize| -> f64 {
+            game[i]
+                .iter()
+                .zip(&equilibrium.second)
+                .map(|(x, q)| x * q)
+                .sum()
+        };
+
+        assert!((equilibrium.value - 0.5).abs() < 1e-6);
+        for (p, expected) in equilibrium.first.iter().zip([0.7, 0.3, 0.]) {
+            assert!((p - expected).abs() < 1e-6);
+        }
+        for (q, expected) in equilibrium.second.iter().zip([0.5, 0.5]) {
+            assert!((q - expected).abs() < 1e-6);
+        }
+        // The value of the primal program is the one the dual strategy guarantees
+        let primal = (0..3).map(conceded).fold(f64::MIN, f64::max);
+        let dual = (0..2).map(guaranteed).fold(f64::MAX, f64::min);
 
-    GameSolution {
-        cost: solution.value(cost),
-        distribution: cols.into_iter().map(|col| solution.value(col)).collect(),
+        assert!((primal - equilibrium.value).abs() < 1e-6);
+        assert!((dual - equilibrium.value).abs() < 1e-6);
     }
 }
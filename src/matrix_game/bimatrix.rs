@@ -0,0 +1,402 @@
+use crate::error::MontyError;
+use crate::matrix_game::PayoffMatrix;
+
+/// Tolerance used in pivoting and when checking equilibria
+const EPSILON: f64 = 1e-9;
+
+/// Two-player general-sum game in normal form: rows are the first player's strategies, columns are
+/// the second player's ones
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawBimatrixGame"))]
+pub struct BimatrixGame {
+    /// Payoffs of the first player
+    pub first: PayoffMatrix,
+    /// Payoffs of the second player
+    pub second: PayoffMatrix,
+}
+
+/// Fields of a deserialized `BimatrixGame`, checked by `new`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawBimatrixGame {
+    first: PayoffMatrix,
+    second: PayoffMatrix,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawBimatrixGame> for BimatrixGame {
+    type Error = MontyError;
+
+    fn try_from(value: RawBimatrixGame) -> Result<Self, Self::Error> {
+        Self::new(value.first, value.second)
+    }
+}
+
+/// Nash equilibrium of a bimatrix game in mixed strategies
+#[derive(Debug, Default, Clone, PartialOrd, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct BimatrixEquilibrium {
+    /// Distribution over the rows
+    pub first: Vec<f64>,
+    /// Distribution over the columns
+    pub second: Vec<f64>,
+    /// Expected payoffs of the first and the second player
+    pub payoffs: [f64; 2],
+}
+
+/// Tableau of one of the best response polytopes in the equality form `T z = 1`, where the
+/// variables are indexed by their labels
+struct Tableau {
+    rows: Vec<Vec<f64>>,
+    rhs: Vec<f64>,
+    basis: Vec<usize>,
+    /// Labels of the slack variables of the initial basis, used by the lexicographic ratio test
+    slacks: Vec<usize>,
+}
+
+impl Tableau {
+    /// Tableau of `{ z >= 0 : M z <= 1 }` where the labels of the variables of `z` start at
+    /// `offset` and the labels of the slacks start at `slack_offset`
+    fn new(matrix: &[Vec<f64>], labels: usize, offset: usize, slack_offset: usize) -> Self {
+        let rows = matrix
+            .iter()
+            .enumerate()
+            .map(|(r, row)| {
+                let mut coefficients = vec![0.; labels];
+                for (j, x) in row.iter().enumerate() {
+                    coefficients[offset + j] = *x;
+                }
+                coefficients[slack_offset + r] = 1.;
+                coefficients
+            })
+            .collect();
+        let slacks: Vec<usize> = (slack_offset..slack_offset + matrix.len()).collect();
+
+        Self {
+            rows,
+            rhs: vec![1.; matrix.len()],
+            basis: slacks.clone(),
+            slacks,
+        }
+    }
+
+    /// Brings the variable `entering` into the basis and returns the label that leaves it
+    fn pivot(&mut self, entering: usize) -> Result<usize, MontyError> {
+        let key = |r: usize| -> Vec<f64> {
+            let c = self.rows[r][entering];
+            std::iter::once(self.rhs[r] / c)
+                .chain(self.slacks.iter().map(|s| self.rows[r][*s] / c))
+                .collect()
+        };
+
+        let r = (0..self.rows.len())
+            .filter(|r| self.rows[*r][entering] > EPSILON)
+            .min_by(|a, b| {
+                key(*a)
+                    .iter()
+                    .zip(key(*b))
+                    .map(|(x, y)| x.total_cmp(&y))
+                    .find(|o| o.is_ne())
+                    .unwrap_or(std::cmp::Ordering::Equal)
+            })
+            .ok_or_else(|| MontyError::Solver(String::from("unbounded pivot")))?;
+
+        let c = self.rows[r][entering];
+        for x in &mut self.rows[r] {
+            *x /= c;
+        }
+        self.rhs[r] /= c;
+
+        let pivot = self.rows[r].clone();
+        for k in (0..self.rows.len()).filter(|k| *k != r) {
+            let factor = self.rows[k][entering];
+            if factor != 0. {
+                for (x, y) in self.rows[k].iter_mut().zip(&pivot) {
+                    *x -= factor * y;
+                }
+                self.rhs[k] -= factor * self.rhs[r];
+            }
+        }
+
+        Ok(std::mem::replace(&mut self.basis[r], entering))
+    }
+
+    /// Normalized values of the variables with labels in `range`
+    fn strategy(&self, range: std::ops::Range<usize>) -> Vec<f64> {
+        let mut ans = vec![0.; range.len()];
+
+        for (r, b) in self.basis.iter().enumerate() {
+            if range.contains(b) {
+                ans[b - range.start] = self.rhs[r].max(0.);
+            }
+        }
+
+        let total: f64 = ans.iter().sum();
+        ans.iter().map(|x| x / total).collect()
+    }
+}
+
+/// Solves the square linear system `a x = b` by Gaussian elimination
+fn solve_linear(mut a: Vec<Vec<f64>>, mut b: Vec<f64>) -> Option<Vec<f64>> {
+    let n = b.len();
+
+    for c in 0..n {
+        let p = (c..n).max_by(|x, y| a[*x][c].abs().total_cmp(&a[*y][c].abs()))?;
+        if a[p][c].abs() < EPSILON {
+            return None;
+        }
+        a.swap(c, p);
+        b.swap(c, p);
+
+        let pivot = a[c].clone();
+        for r in (0..n).filter(|r| *r != c) {
+            let factor = a[r][c] / pivot[c];
+            for (x, y) in a[r].iter_mut().zip(&pivot).skip(c) {
+                *x -= factor * y;
+            }
+            b[r] -= factor * b[c];
+        }
+    }
+
+    Some((0..n).map(|c| b[c] / a[c][c]).collect())
+}
+
+/// Subsets of `0..n` of size `k` as sorted vectors
+fn subsets(n: usize, k: usize) -> Vec<Vec<usize>> {
+    if k == 0 {
+        return vec![vec![]];
+    }
+    if n < k {
+        return vec![];
+    }
+
+    let mut ans = subsets(n - 1, k);
+    for mut s in subsets(n - 1, k - 1) {
+        s.push(n - 1);
+        ans.push(s);
+    }
+
+    ans
+}
+
+/// Mixture over `support` making every strategy of the opponent in `against` equally good for
+/// the opponent, whose payoff is `payoff(own, opponent's)`
+fn indifferent(
+    support: &[usize],
+    against: &[usize],
+    payoff: impl Fn(usize, usize) -> f64,
+) -> Option<Vec<f64>> {
+    let k = support.len();
+    // Unknowns: the probabilities of the support and the opponent's payoff
+    let mut a: Vec<Vec<f64>> = against
+        .iter()
+        .map(|j| {
+            support
+                .iter()
+                .map(|i| payoff(*i, *j))
+                .chain([-1.])
+                .collect()
+        })
+        .collect();
+    a.push(vec![1.; k].into_iter().chain([0.]).collect());
+    let mut b = vec![0.; k];
+    b.push(1.);
+
+    let solution = solve_linear(a, b)?;
+
+    if solution[..k].iter().all(|p| *p >= -EPSILON) {
+        Some(solution[..k].to_vec())
+    } else {
+        None
+    }
+}
+
+impl BimatrixGame {
+    pub fn new(first: PayoffMatrix, second: PayoffMatrix) -> Result<Self, MontyError> {
+        if first.n_rows() != second.n_rows() || first.n_cols() != second.n_cols() {
+            return Err(MontyError::ShapeMismatch);
+        }
+
+        Ok(Self { first, second })
+    }
+
+    /// The bimatrix form of a zero-sum game
+    pub fn zero_sum(game: PayoffMatrix) -> Self {
+        Self {
+            second: -game.clone(),
+            first: game,
+        }
+    }
+
+    /// Expected payoffs of both players when they play mixed strategies
+    pub fn payoffs(&self, first: &[f64], second: &[f64]) -> [f64; 2] {
+        let value = |game: &PayoffMatrix| -> f64 {
+            game.iter()
+                .zip(first)
+                .map(|(row, p)| row.iter().zip(second).map(|(x, q)| x * q).sum::<f64>() * p)
+                .sum()
+        };
+
+        [value(&self.first), value(&self.second)]
+    }
+
+    fn equilibrium(&self, first: Vec<f64>, second: Vec<f64>) -> BimatrixEquilibrium {
+        BimatrixEquilibrium {
+            payoffs: self.payoffs(&first, &second),
+            first,
+            second,
+        }
+    }
+
+    /// Whether neither player gains by deviating to a pure strategy
+    pub fn is_equilibrium(&self, first: &[f64], second: &[f64]) -> bool {
+        let [a, b] = self.payoffs(first, second);
+        let m = self.first.n_rows();
+        let n = self.first.n_cols();
+
+        (0..m).all(|i| {
+            let row: Vec<f64> = (0..m).map(|k| if k == i { 1. } else { 0. }).collect();
+            self.payoffs(&row, second)[0] <= a + 1e-7
+        }) && (0..n).all(|j| {
+            let col: Vec<f64> = (0..n).map(|k| if k == j { 1. } else { 0. }).collect();
+            self.payoffs(first, &col)[1] <= b + 1e-7
+        })
+    }
+
+    /// Finds an equilibrium by the Lemke–Howson algorithm
+    ///
+    /// Labels `0..m` stand for the rows and `m..m + n` for the columns; starting from different
+    /// `dropped` labels may lead to different equilibria
+    pub fn lemke_howson(&self, dropped: usize) -> BimatrixEquilibrium {
+        self.try_lemke_howson(dropped).unwrap()
+    }
+
+    pub fn try_lemke_howson(&self, dropped: usize) -> Result<BimatrixEquilibrium, MontyError> {
+        let m = self.first.n_rows();
+        let n = self.first.n_cols();
+        let labels = m + n;
+
+        if m == 0 || n == 0 {
+            return Ok(Default::default());
+        }
+
+        if dropped >= labels {
+            return Err(MontyError::BadParameter {
+                name: String::from("dropped"),
+                message: format!("there are only {labels} labels"),
+            });
+        }
+
+        // The algorithm needs positive payoffs, which adding a constant does not affect
+        let shift = |game: &PayoffMatrix| -> Vec<Vec<f64>> {
+            let min = game.iter().flatten().copied().fold(f64::INFINITY, f64::min);
+            game.iter()
+                .map(|row| row.iter().map(|x| x - min + 1.).collect())
+                .collect()
+        };
+
+        // P = { x >= 0 : B^T x <= 1 } and Q = { y >= 0 : A y <= 1 }
+        let mut polytopes = [
+            Tableau::new(&shift(&self.second.transpose()), labels, 0, m),
+            Tableau::new(&shift(&self.first), labels, m, 0),
+        ];
+        let mut current = if dropped < m { 0 } else { 1 };
+        let mut entering = dropped;
+
+        for _ in 0..10_000 {
+            let leaving = polytopes[current].pivot(entering)?;
+
+            if leaving == dropped {
+                let first = polytopes[0].strategy(0..m);
+                let second = polytopes[1].strategy(m..labels);
+
+                return Ok(self.equilibrium(first, second));
+            }
+
+            entering = leaving;
+            current = 1 - current;
+        }
+
+        Err(MontyError::Solver(String::from(
+            "Lemke–Howson did not terminate",
+        )))
+    }
+
+    /// Finds all the equilibria of a nondegenerate game by trying every pair of supports of equal
+    /// sizes; the running time is exponential in the size of the game
+    pub fn support_enumeration(&self) -> Vec<BimatrixEquilibrium> {
+        let m = self.first.n_rows();
+        let n = self.first.n_cols();
+        let mut ans: Vec<BimatrixEquilibrium> = vec![];
+
+        for k in 1..=m.min(n) {
+            for rows in subsets(m, k) {
+                for cols in subsets(n, k) {
+                    let Some(x) = indifferent(&rows, &cols, |i, j| self.second[i][j]) else {
+                        continue;
+                    };
+                    let Some(y) = indifferent(&cols, &rows, |j, i| self.first[i][j]) else {
+                        continue;
+                    };
+
+                    let mut first = vec![0.; m];
+                    let mut second = vec![0.; n];
+                    for (i, p) in rows.iter().zip(x) {
+                        first[*i] = p.max(0.);
+                    }
+                    for (j, p) in cols.iter().zip(y) {
+                        second[*j] = p.max(0.);
+                    }
+
+                    let duplicate = ans.iter().any(|e| {
+                        e.first
+                            .iter()
+                            .chain(&e.second)
+                            .zip(first.iter().chain(&second))
+                            .all(|(a, b)| (a - b).abs() < 1e-7)
+                    });
+
+                    if !duplicate && self.is_equilibrium(&first, &second) {
+                        ans.push(self.equilibrium(first, second));
+                    }
+                }
+            }
+        }
+
+        ans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn battle_of_sexes() -> BimatrixGame {
+        BimatrixGame::new(
+            PayoffMatrix::new(vec![vec![3., 0.], vec![0., 2.]]).unwrap(),
+            PayoffMatrix::new(vec![vec![2., 0.], vec![0., 3.]]).unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn battle_of_sexes_equilibria() {
+        let game = battle_of_sexes();
+
+        assert_eq!(game.support_enumeration().len(), 3);
+        for dropped in 0..4 {
+            let equilibrium = game.lemke_howson(dropped);
+
+            assert!(game.is_equilibrium(&equilibrium.first, &equilibrium.second));
+        }
+    }
+
+    #[test]
+    fn lemke_howson_rejects_unknown_labels() {
+        assert!(matches!(
+            battle_of_sexes().try_lemke_howson(4),
+            Err(MontyError::BadParameter { .. })
+        ));
+    }
+}
@@ -0,0 +1,264 @@
+use crate::error::MontyError;
+use crate::matrix_game::{reverse_game, Equilibrium, PayoffMatrix};
+use good_lp::{clarabel, variable, variables, Expression, Solution, SolverModel, Variable};
+use std::fmt::{Display, Formatter};
+
+/// Tolerance used when comparing payoffs
+const EPSILON: f64 = 1e-9;
+
+#[derive(Debug, Default, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum DominanceKind {
+    /// Another pure strategy is better against every opponent's strategy
+    #[default]
+    Strict,
+    /// Another pure strategy is never worse and sometimes better
+    Weak,
+    /// A mixture of other pure strategies is better against every opponent's strategy
+    Mixed,
+}
+
+#[derive(Debug, Copy, Clone, Ord, PartialOrd, Eq, PartialEq, Hash)]
+pub enum Line {
+    Row(usize),
+    Column(usize),
+}
+
+/// A single elimination, with indices of the original matrix
+#[derive(Debug, Clone, PartialEq)]
+pub struct EliminationStep {
+    pub eliminated: Line,
+    pub kind: DominanceKind,
+    /// The dominating rows or columns with their probabilities
+    pub by: Vec<(usize, f64)>,
+}
+
+impl Display for EliminationStep {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let (name, index) = match self.eliminated {
+            Line::Row(i) => ("row", i),
+            Line::Column(j) => ("column", j),
+        };
+        let kind = match self.kind {
+            DominanceKind::Strict => "strictly",
+            DominanceKind::Weak => "weakly",
+            DominanceKind::Mixed => "strictly",
+        };
+
+        write!(f, "{name} {index} is {kind} dominated by ")?;
+
+        match self.by.as_slice() {
+            [(k, _)] => write!(f, "{name} {k}"),
+            by => {
+                write!(f, "the mixture of")?;
+                for (k, p) in by {
+                    write!(f, " {name} {k} ({p:.3})")?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+/// Result of the iterated elimination of dominated strategies
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Elimination {
+    pub matrix: PayoffMatrix,
+    /// Original indices of the remaining rows
+    pub rows: Vec<usize>,
+    /// Original indices of the remaining columns
+    pub cols: Vec<usize>,
+    pub steps: Vec<EliminationStep>,
+    shape: (usize, usize),
+}
+
+impl Elimination {
+    /// Turns an equilibrium of the reduced matrix into an equilibrium of the original one
+    pub fn lift(&self, equilibrium: &Equilibrium) -> Equilibrium {
+        let mut first = vec![0.; self.shape.0];
+        let mut second = vec![0.; self.shape.1];
+
+        for (i, p) in self.rows.iter().zip(&equilibrium.first) {
+            first[*i] = *p;
+        }
+        for (j, p) in self.cols.iter().zip(&equilibrium.second) {
+            second[*j] = *p;
+        }
+
+        Equilibrium {
+            value: equilibrium.value,
+            first,
+            second,
+        }
+    }
+}
+
+/// A dominated row and the rows dominating it with their probabilities
+type Dominated = Option<(usize, Vec<(usize, f64)>)>;
+
+/// Finds a row of `game` restricted to `rows` and `cols` dominated by pure rows
+fn pure_dominated(
+    game: &PayoffMatrix,
+    rows: &[usize],
+    cols: &[usize],
+    strict: bool,
+) -> Option<(usize, usize)> {
+    for &i in rows {
+        for &k in rows.iter().filter(|k| **k != i) {
+            let dominates = if strict {
+                cols.iter().all(|j| game[k][*j] > game[i][*j] + EPSILON)
+            } else {
+                cols.iter().all(|j| game[k][*j] >= game[i][*j] - EPSILON)
+                    && cols.iter().any(|j| game[k][*j] > game[i][*j] + EPSILON)
+            };
+
+            if dominates {
+                return Some((i, k));
+            }
+        }
+    }
+
+    None
+}
+
+/// Finds a row of `game` restricted to `rows` and `cols` strictly dominated by a mixture of the
+/// other rows by maximizing the margin of the mixture
+fn mixed_dominated(
+    game: &PayoffMatrix,
+    rows: &[usize],
+    cols: &[usize],
+) -> Result<Dominated, MontyError> {
+    if rows.len() < 2 {
+        return Ok(None);
+    }
+
+    for &i in rows {
+        let others: Vec<usize> = rows.iter().copied().filter(|k| *k != i).collect();
+
+        variables! {problem: margin;}
+        let mixture: Vec<Variable> = problem.add_vector(variable().bounds(0..=1), others.len());
+        let total: Expression = mixture.iter().sum();
+        let mut model = problem
+            .maximise(margin)
+            .using(clarabel)
+            .with(total.eq(1.))
+            .with(Expression::from(margin).leq(1.));
+
+        for j in cols {
+            let payoff: Expression = others
+                .iter()
+                .zip(&mixture)
+                .map(|(k, x)| game[*k][*j] * *x)
+                .sum();
+            model = model.with(payoff.geq(game[i][*j] + margin));
+        }
+
+        let solution = model.solve()?;
+
+        if solution.value(margin) > 1e-7 {
+            let by = others
+                .iter()
+                .zip(&mixture)
+                .map(|(k, x)| (*k, solution.value(*x)))
+                .filter(|(_, p)| *p > 1e-7)
+                .collect();
+
+            return Ok(Some((i, by)));
+        }
+    }
+
+    Ok(None)
+}
+
+fn dominated(
+    game: &PayoffMatrix,
+    rows: &[usize],
+    cols: &[usize],
+    kind: DominanceKind,
+) -> Result<Dominated, MontyError> {
+    match kind {
+        DominanceKind::Strict | DominanceKind::Weak => {
+            Ok(
+                pure_dominated(game, rows, cols, kind == DominanceKind::Strict)
+                    .map(|(i, k)| (i, vec![(k, 1.)])),
+            )
+        }
+        DominanceKind::Mixed => mixed_dominated(game, rows, cols),
+    }
+}
+
+/// Iteratively removes dominated rows and columns, one at a time
+pub fn eliminate_dominated(game: &PayoffMatrix, kind: DominanceKind) -> Elimination {
+    try_eliminate_dominated(game, kind).unwrap()
+}
+
+pub fn try_eliminate_dominated(
+    game: &PayoffMatrix,
+    kind: DominanceKind,
+) -> Result<Elimination, MontyError> {
+    let shape = (game.n_rows(), game.n_cols());
+    // The columns' player minimizes, so their dominance is that of the rows of the reversed game
+    let reversed = reverse_game(game);
+    let mut rows: Vec<usize> = (0..shape.0).collect();
+    let mut cols: Vec<usize> = (0..shape.1).collect();
+    let mut steps = vec![];
+
+    loop {
+        if let Some((i, by)) = dominated(game, &rows, &cols, kind)? {
+            rows.retain(|k| *k != i);
+            steps.push(EliminationStep {
+                eliminated: Line::Row(i),
+                kind,
+                by,
+            });
+        } else if let Some((j, by)) = dominated(&reversed, &cols, &rows, kind)? {
+            cols.retain(|k| *k != j);
+            steps.push(EliminationStep {
+                eliminated: Line::Column(j),
+                kind,
+                by,
+            });
+        } else {
+            break;
+        }
+    }
+
+    Ok(Elimination {
+        matrix: game.select(&rows, &cols),
+        rows,
+        cols,
+        steps,
+        shape,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::matrix_game::solve_zero_sum;
+
+    #[test]
+    fn elimination_keeps_the_value() {
+        let game = PayoffMatrix::new(vec![
+            vec![3., 1., 4.],
+            vec![2., 0., 3.],
+            vec![1., 2., 0.],
+            vec![2., 1.5, 1.],
+        ])
+        .unwrap();
+        let value = solve_zero_sum(&game).value;
+
+        for kind in [
+            DominanceKind::Strict,
+            DominanceKind::Weak,
+            DominanceKind::Mixed,
+        ] {
+            let elimination = eliminate_dominated(&game, kind);
+            let equilibrium = elimination.lift(&solve_zero_sum(&elimination.matrix));
+
+            assert!(!elimination.steps.is_empty());
+            assert!(!elimination.rows.contains(&1));
+            assert!((equilibrium.value - value).abs() < 1e-6);
+            assert_eq!(equilibrium.first.len(), 4);
+        }
+    }
+}
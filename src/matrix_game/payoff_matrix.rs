@@ -0,0 +1,227 @@
+use crate::error::MontyError;
+use std::fmt::{Display, Formatter};
+use std::ops::{Index, Neg};
+
+/// Rectangular matrix of the rows' player payoffs with labelled rows and columns
+#[derive(Debug, Default, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "RawPayoffMatrix"))]
+pub struct PayoffMatrix {
+    data: Vec<Vec<f64>>,
+    rows: Vec<String>,
+    cols: Vec<String>,
+}
+
+/// Fields of a deserialized `PayoffMatrix`, checked by `new` and `with_labels`
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct RawPayoffMatrix {
+    data: Vec<Vec<f64>>,
+    rows: Vec<String>,
+    cols: Vec<String>,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<RawPayoffMatrix> for PayoffMatrix {
+    type Error = MontyError;
+
+    fn try_from(value: RawPayoffMatrix) -> Result<Self, Self::Error> {
+        Self::new(value.data)?.with_labels(value.rows, value.cols)
+    }
+}
+
+impl PayoffMatrix {
+    /// Checks that all the rows have the same length and labels rows and columns by their indices
+    pub fn new(data: Vec<Vec<f64>>) -> Result<Self, MontyError> {
+        let expected = data.first().map(|row| row.len()).unwrap_or_default();
+
+        if let Some(row) = data.iter().position(|row| row.len() != expected) {
+            return Err(MontyError::RaggedMatrix {
+                row,
+                expected,
+                found: data[row].len(),
+            });
+        }
+
+        Ok(Self {
+            rows: (0..data.len()).map(|i| i.to_string()).collect(),
+            cols: (0..expected).map(|j| j.to_string()).collect(),
+            data,
+        })
+    }
+
+    /// Replaces the labels, failing with `ShapeMismatch` unless there is one per row and column
+    pub fn with_labels(self, rows: Vec<String>, cols: Vec<String>) -> Result<Self, MontyError> {
+        if rows.len() != self.n_rows() || cols.len() != self.n_cols() {
+            return Err(MontyError::ShapeMismatch);
+        }
+
+        Ok(Self { rows, cols, ..self })
+    }
+
+    pub fn n_rows(&self) -> usize {
+        self.rows.len()
+    }
+
+    pub fn n_cols(&self) -> usize {
+        self.cols.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.data.is_empty()
+    }
+
+    pub fn row_labels(&self) -> &[String] {
+        &self.rows
+    }
+
+    pub fn col_labels(&self) -> &[String] {
+        &self.cols
+    }
+
+    pub fn iter(&self) -> std::slice::Iter<'_, Vec<f64>> {
+        self.data.iter()
+    }
+
+    pub fn transpose(&self) -> Self {
+        Self {
+            data: (0..self.n_cols())
+                .map(|j| self.data.iter().map(|row| row[j]).collect())
+                .collect(),
+            rows: self.cols.clone(),
+            cols: self.rows.clone(),
+        }
+    }
+
+    /// The same game from the point of view of the columns' player
+    pub fn reverse(&self) -> Self {
+        -self.transpose()
+    }
+
+    /// Sub-matrix of the given rows and columns in the given order
+    pub fn select(&self, rows: &[usize], cols: &[usize]) -> Self {
+        Self {
+            data: rows
+                .iter()
+                .map(|i| cols.iter().map(|j| self.data[*i][*j]).collect())
+                .collect(),
+            rows: rows.iter().map(|i| self.rows[*i].clone()).collect(),
+            cols: cols.iter().map(|j| self.cols[*j].clone()).collect(),
+        }
+    }
+}
+
+impl Index<usize> for PayoffMatrix {
+    type Output = [f64];
+
+    fn index(&self, index: usize) -> &Self::Output {
+        &self.data[index]
+    }
+}
+
+impl Neg for PayoffMatrix {
+    type Output = Self;
+
+    fn neg(mut self) -> Self::Output {
+        for x in self.data.iter_mut().flatten() {
+            *x = -*x;
+        }
+
+        self
+    }
+}
+
+impl<'a> IntoIterator for &'a PayoffMatrix {
+    type Item = &'a Vec<f64>;
+    type IntoIter = std::slice::Iter<'a, Vec<f64>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+impl Display for PayoffMatrix {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        let width = self
+            .rows
+            .iter()
+            .map(|s| s.chars().count())
+            .max()
+            .unwrap_or_default();
+        let cells: Vec<Vec<String>> = self
+            .data
+            .iter()
+            .map(|row| row.iter().map(|x| format!("{x:.4}")).collect())
+            .collect();
+        let widths: Vec<usize> = self
+            .cols
+            .iter()
+            .enumerate()
+            .map(|(j, label)| {
+                cells
+                    .iter()
+                    .map(|row| row[j].chars().count())
+                    .chain([label.chars().count()])
+                    .max()
+                    .unwrap()
+            })
+            .collect();
+
+        write!(f, "{:width$}", "")?;
+        for (label, w) in self.cols.iter().zip(&widths) {
+            write!(f, " | {label:>w$}")?;
+        }
+        writeln!(f)?;
+
+        for (label, row) in self.rows.iter().zip(&cells) {
+            write!(f, "{label:width$}")?;
+            for (cell, w) in row.iter().zip(&widths) {
+                write!(f, " | {cell:>w$}")?;
+            }
+            writeln!(f)?;
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn labels_must_match_the_shape() {
+        let matrix = PayoffMatrix::new(vec![vec![1., 2.], vec![3., 4.]]).unwrap();
+        let labels = |n: usize| (0..n).map(|k| format!("s{k}")).collect::<Vec<_>>();
+
+        assert!(matrix.clone().with_labels(labels(2), labels(2)).is_ok());
+        assert_eq!(
+            matrix.clone().with_labels(labels(1), labels(2)),
+            Err(MontyError::ShapeMismatch)
+        );
+        assert_eq!(
+            matrix.with_labels(labels(2), labels(3)),
+            Err(MontyError::ShapeMismatch)
+        );
+    }
+
+    /// Labels are padded by their characters rather than their bytes; every character is assumed
+    /// to be one column wide
+    #[test]
+    fn display_aligns_multibyte_characters() {
+        let matrix = PayoffMatrix::new(vec![vec![1.], vec![-1.]])
+            .unwrap()
+            .with_labels(
+                vec![String::from("é"), String::from("ab")],
+                vec![String::from("ü")],
+            )
+            .unwrap();
+        let lines: Vec<usize> = matrix
+            .to_string()
+            .lines()
+            .map(|line| line.chars().count())
+            .collect();
+
+        assert!(lines.iter().all(|n| *n == lines[0]), "{matrix}");
+    }
+}
@@ -0,0 +1,425 @@
+use crate::error::MontyError;
+use crate::game_tree::dyn_game::{build, DynGame};
+use crate::games::{CyclicGame, Guess, Host, KuhnPoker, LeducHoldem, MontyHall, Placement};
+use std::fmt::{Display, Formatter};
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParamKind {
+    Float,
+    Integer,
+    /// One of the listed names
+    Choice(&'static [&'static str]),
+}
+
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub enum ParamValue {
+    Float(f64),
+    Integer(i64),
+    Choice(&'static str),
+}
+
+impl Display for ParamValue {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ParamValue::Float(x) => write!(f, "{x}"),
+            ParamValue::Integer(x) => write!(f, "{x}"),
+            ParamValue::Choice(x) => write!(f, "{x}"),
+        }
+    }
+}
+
+/// Parameter of a game constructor
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct ParamSpec {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub kind: ParamKind,
+    pub default: ParamValue,
+}
+
+impl ParamSpec {
+    fn bad(&self, message: impl Into<String>) -> MontyError {
+        MontyError::BadParameter {
+            name: String::from(self.name),
+            message: message.into(),
+        }
+    }
+
+    pub fn parse(&self, value: &str) -> Result<ParamValue, MontyError> {
+        match self.kind {
+            ParamKind::Float => value
+                .parse()
+                .map(ParamValue::Float)
+                .map_err(|_| self.bad(format!("{value} is not a number"))),
+            ParamKind::Integer => value
+                .parse()
+                .map(ParamValue::Integer)
+                .map_err(|_| self.bad(format!("{value} is not an integer"))),
+            ParamKind::Choice(names) => names
+                .iter()
+                .find(|name| **name == value)
+                .map(|name| ParamValue::Choice(name))
+                .ok_or_else(|| self.bad(format!("{value} is not one of {}", names.join(", ")))),
+        }
+    }
+}
+
+/// Values of all the parameters of a game constructor
+#[derive(Debug, Clone, PartialEq)]
+pub struct Params {
+    values: Vec<(ParamSpec, ParamValue)>,
+}
+
+impl Params {
+    fn get(&self, name: &str) -> Result<&(ParamSpec, ParamValue), MontyError> {
+        self.values
+            .iter()
+            .find(|(spec, _)| spec.name == name)
+            .ok_or_else(|| MontyError::BadParameter {
+                name: String::from(name),
+                message: String::from("no such parameter"),
+            })
+    }
+
+    pub fn float(&self, name: &str) -> Result<f64, MontyError> {
+        match self.get(name)? {
+            (_, ParamValue::Float(x)) => Ok(*x),
+            (spec, _) => Err(spec.bad("not a float parameter")),
+        }
+    }
+
+    pub fn integer(&self, name: &str) -> Result<i64, MontyError> {
+        match self.get(name)? {
+            (_, ParamValue::Integer(x)) => Ok(*x),
+            (spec, _) => Err(spec.bad("not an integer parameter")),
+        }
+    }
+
+    pub fn choice(&self, name: &str) -> Result<&'static str, MontyError> {
+        match self.get(name)? {
+            (_, ParamValue::Choice(x)) => Ok(x),
+            (spec, _) => Err(spec.bad("not a choice parameter")),
+        }
+    }
+
+    /// Finite float parameter greater than zero
+    pub fn positive(&self, name: &str) -> Result<f64, MontyError> {
+        let (spec, _) = self.get(name)?;
+        let x = self.float(name)?;
+
+        if x.is_finite() && x > 0. {
+            Ok(x)
+        } else {
+            Err(spec.bad(format!("{x} is not positive")))
+        }
+    }
+
+    /// Integer parameter within `range`
+    pub fn integer_in<T: TryFrom<i64>>(
+        &self,
+        name: &str,
+        range: std::ops::RangeInclusive<i64>,
+    ) -> Result<T, MontyError> {
+        let (spec, _) = self.get(name)?;
+        let x = self.integer(name)?;
+
+        if range.contains(&x) {
+            T::try_from(x).map_err(|_| spec.bad(format!("{x} is out of range")))
+        } else {
+            Err(spec.bad(format!(
+                "{x} is not between {} and {}",
+                range.start(),
+                range.end()
+            )))
+        }
+    }
+}
+
+pub type Constructor = fn(&Params) -> Result<Box<dyn DynGame>, MontyError>;
+
+/// Named game with its parameters
+#[derive(Debug, Clone)]
+pub struct GameEntry {
+    pub name: &'static str,
+    pub description: &'static str,
+    pub params: Vec<ParamSpec>,
+    pub constructor: Constructor,
+}
+
+impl GameEntry {
+    /// Builds the game from the values of the parameters given as strings; the missing ones
+    /// take their default values
+    pub fn build(&self, params: &[(&str, &str)]) -> Result<Box<dyn DynGame>, MontyError> {
+        if let Some((name, _)) = params
+            .iter()
+            .find(|(name, _)| self.params.iter().all(|spec| spec.name != *name))
+        {
+            return Err(MontyError::BadParameter {
+                name: String::from(*name),
+                message: format!("{} has no such parameter", self.name),
+            });
+        }
+
+        let values = self
+            .params
+            .iter()
+            .map(|spec| {
+                match params.iter().rev().find(|(name, _)| *name == spec.name) {
+                    None => Ok(spec.default),
+                    Some((_, value)) => spec.parse(value),
+                }
+                .map(|value| (*spec, value))
+            })
+            .collect::<Result<_, _>>()?;
+
+        (self.constructor)(&Params { values })
+    }
+}
+
+/// Games that front-ends can select by name
+#[derive(Debug, Default, Clone)]
+pub struct Registry {
+    games: Vec<GameEntry>,
+}
+
+impl Registry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Replaces the game with the same name, if any
+    pub fn register(&mut self, entry: GameEntry) {
+        self.games.retain(|game| game.name != entry.name);
+        self.games.push(entry);
+    }
+
+    pub fn games(&self) -> &[GameEntry] {
+        &self.games
+    }
+
+    pub fn get(&self, name: &str) -> Option<&GameEntry> {
+        self.games.iter().find(|game| game.name == name)
+    }
+
+    pub fn build(
+        &self,
+        name: &str,
+        params: &[(&str, &str)],
+    ) -> Result<Box<dyn DynGame>, MontyError> {
+        self.get(name)
+            .ok_or_else(|| MontyError::UnknownGame(String::from(name)))?
+            .build(params)
+    }
+
+    /// Registry of the games of this crate
+    pub fn with_builtin_games() -> Self {
+        let mut ans = Self::new();
+
+        ans.register(GameEntry {
+            name: "guess",
+            description: "guess the outcome of a random event",
+            params: vec![],
+            constructor: |_| build(Guess::default()),
+        });
+        ans.register(GameEntry {
+            name: "rps",
+            description: "rock-paper-scissors",
+            params: vec![],
+            constructor: |_| build(CyclicGame::default()),
+        });
+        ans.register(GameEntry {
+            name: "twisted-rps",
+            description: "rock-paper-scissors with a different payoff of paper ties",
+            params: vec![ParamSpec {
+                name: "twist",
+                description: "payoff of the first player when both show paper",
+                kind: ParamKind::Float,
+                default: ParamValue::Float(2.),
+            }],
+            constructor: |params| {
+                build(CyclicGame::new(3).with_ties(vec![0., params.float("twist")?, 0.]))
+            },
+        });
+        ans.register(GameEntry {
+            name: "cyclic",
+            description: "rock-paper-scissors with any odd number of gestures",
+            params: vec![ParamSpec {
+                name: "gestures",
+                description: "number of gestures, e.g. 5 for rock-paper-scissors-Spock-lizard",
+                kind: ParamKind::Integer,
+                default: ParamValue::Integer(5),
+            }],
+            constructor: |params| {
+                let gestures: u8 = params.integer_in("gestures", 1..=255)?;
+
+                build(CyclicGame::try_new(gestures)?)
+            },
+        });
+        ans.register(GameEntry {
+            name: "kuhn-poker",
+            description: "one round of betting with a three-card deck",
+            params: vec![],
+            constructor: |_| build(KuhnPoker::default()),
+        });
+        ans.register(GameEntry {
+            name: "leduc-holdem",
+            description: "two rounds of betting with a private and a public card",
+            params: vec![
+                ParamSpec {
+                    name: "ranks",
+                    description: "number of ranks in the deck",
+                    kind: ParamKind::Integer,
+                    default: ParamValue::Integer(3),
+                },
+                ParamSpec {
+                    name: "suits",
+                    description: "number of cards of each rank",
+                    kind: ParamKind::Integer,
+                    default: ParamValue::Integer(2),
+                },
+                ParamSpec {
+                    name: "small-bet",
+                    description: "size of the bets in the first round",
+                    kind: ParamKind::Float,
+                    default: ParamValue::Float(2.),
+                },
+                ParamSpec {
+                    name: "big-bet",
+                    description: "size of the bets in the second round",
+                    kind: ParamKind::Float,
+                    default: ParamValue::Float(4.),
+                },
+                ParamSpec {
+                    name: "raises",
+                    description: "number of raises allowed in each round",
+                    kind: ParamKind::Integer,
+                    default: ParamValue::Integer(1),
+                },
+            ],
+            constructor: |params| {
+                let ranks: u8 = params.integer_in("ranks", 1..=13)?;
+                let suits: u8 = params.integer_in("suits", i64::from(3u8.div_ceil(ranks))..=4)?;
+                let raises: u8 = params.integer_in("raises", 0..=4)?;
+                let bets = [params.positive("small-bet")?, params.positive("big-bet")?];
+
+                build(LeducHoldem::try_new(ranks, suits, bets, raises)?)
+            },
+        });
+        ans.register(GameEntry {
+            name: "monty-hall",
+            description: "the contestant against the host of a game show",
+            params: vec![
+                ParamSpec {
+                    name: "doors",
+                    description: "number of doors",
+                    kind: ParamKind::Integer,
+                    default: ParamValue::Integer(3),
+                },
+                ParamSpec {
+                    name: "opened",
+                    description: "number of doors the host opens",
+                    kind: ParamKind::Integer,
+                    default: ParamValue::Integer(1),
+                },
+                ParamSpec {
+                    name: "host",
+                    description: "how the host chooses the doors to open",
+                    kind: ParamKind::Choice(&["standard", "ignorant", "angelic", "evil"]),
+                    default: ParamValue::Choice("standard"),
+                },
+                ParamSpec {
+                    name: "placement",
+                    description: "who places the prize",
+                    kind: ParamKind::Choice(&["random", "host"]),
+                    default: ParamValue::Choice("random"),
+                },
+            ],
+            constructor: |params| {
+                let doors: u8 = params.integer_in("doors", 2..=32)?;
+                let opened: u8 = params.integer_in("opened", 0..=i64::from(doors) - 2)?;
+                let host = match params.choice("host")? {
+                    "standard" => Host::Standard,
+                    "ignorant" => Host::Ignorant,
+                    "angelic" => Host::Angelic,
+                    _ => Host::Evil,
+                };
+                let placement = match params.choice("placement")? {
+                    "random" => Placement::Random,
+                    _ => Placement::Host,
+                };
+
+                build(MontyHall::try_new(doors, opened, host, placement)?)
+            },
+        });
+
+        ans
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn bad_parameter(result: Result<Box<dyn DynGame>, MontyError>) -> String {
+        match result {
+            Err(MontyError::BadParameter { name, .. }) => name,
+            Err(e) => panic!("unexpected error {e}"),
+            Ok(_) => panic!("the parameters were accepted"),
+        }
+    }
+
+    #[test]
+    fn builds_the_builtin_games() {
+        let registry = Registry::with_builtin_games();
+
+        for game in registry.games() {
+            assert!(game.build(&[]).is_ok(), "{}", game.name);
+        }
+        assert!(registry
+            .build("monty-hall", &[("doors", "4"), ("host", "evil")])
+            .is_ok());
+        assert_eq!(
+            registry.build("chess", &[]).err(),
+            Some(MontyError::UnknownGame(String::from("chess")))
+        );
+    }
+
+    #[test]
+    fn rejects_bad_parameters() {
+        let registry = Registry::with_builtin_games();
+
+        for bet in ["0", "-2", "inf", "NaN"] {
+            assert_eq!(
+                bad_parameter(registry.build("leduc-holdem", &[("small-bet", bet)])),
+                "small-bet"
+            );
+        }
+        assert_eq!(
+            bad_parameter(registry.build("monty-hall", &[("doors", "33")])),
+            "doors"
+        );
+        assert_eq!(
+            bad_parameter(registry.build("monty-hall", &[("host", "lazy")])),
+            "host"
+        );
+        assert_eq!(bad_parameter(registry.build("rps", &[("x", "1")])), "x");
+    }
+
+    #[test]
+    fn getters_check_the_kind() {
+        let spec = ParamSpec {
+            name: "doors",
+            description: "",
+            kind: ParamKind::Integer,
+            default: ParamValue::Integer(3),
+        };
+        let params = Params {
+            values: vec![(spec, spec.default)],
+        };
+
+        assert_eq!(params.integer("doors"), Ok(3));
+        assert!(params.float("doors").is_err());
+        assert!(params.choice("doors").is_err());
+        assert!(params.integer("windows").is_err());
+    }
+}
@@ -0,0 +1,215 @@
+//! Versioned JSON and compact binary encodings of the serializable types of this crate
+
+use crate::error::MontyError;
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Version of the layout of the encoded data, bumped on every incompatible change
+pub const FORMAT_VERSION: u32 = 1;
+
+#[derive(Serialize, Deserialize)]
+struct Versioned<T> {
+    version: u32,
+    data: T,
+}
+
+fn check_version(version: u32) -> Result<(), MontyError> {
+    if version == FORMAT_VERSION {
+        Ok(())
+    } else {
+        Err(MontyError::Parse(format!(
+            "format version {version} is not supported, expected {FORMAT_VERSION}"
+        )))
+    }
+}
+
+/// JSON object with the version of the format and the value
+///
+/// JSON needs string keys in maps, so a `NaiveStrategy` or a `BehavioralStrategy` fails to encode
+/// on its own: wrap it in `Pairs`, or store it in a field annotated with
+/// `#[serde(with = "monty_hall::serialization::pairs")]`
+pub fn to_json<T: Serialize>(value: &T) -> Result<String, MontyError> {
+    serde_json::to_string(&Versioned {
+        version: FORMAT_VERSION,
+        data: value,
+    })
+    .map_err(|e| MontyError::Parse(e.to_string()))
+}
+
+pub fn from_json<T: DeserializeOwned>(input: &str) -> Result<T, MontyError> {
+    #[derive(Deserialize)]
+    struct Header {
+        version: u32,
+    }
+
+    let header: Header =
+        serde_json::from_str(input).map_err(|e| MontyError::Parse(e.to_string()))?;
+    check_version(header.version)?;
+
+    let versioned: Versioned<T> =
+        serde_json::from_str(input).map_err(|e| MontyError::Parse(e.to_string()))?;
+
+    Ok(versioned.data)
+}
+
+/// Bincode encoding of the value preceded by the version of the format
+pub fn to_binary<T: Serialize>(value: &T) -> Result<Vec<u8>, MontyError> {
+    bincode::serialize(&Versioned {
+        version: FORMAT_VERSION,
+        data: value,
+    })
+    .map_err(|e| MontyError::Parse(e.to_string()))
+}
+
+pub fn from_binary<T: DeserializeOwned>(input: &[u8]) -> Result<T, MontyError> {
+    let version: u32 = bincode::deserialize(input).map_err(|e| MontyError::Parse(e.to_string()))?;
+    check_version(version)?;
+
+    let versioned: Versioned<T> =
+        bincode::deserialize(input).map_err(|e| MontyError::Parse(e.to_string()))?;
+
+    Ok(versioned.data)
+}
+
+/// Map encoded as a list of key-value pairs, e.g. a strategy to pass to `to_json`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(bound(
+    serialize = "K: Serialize, V: Serialize",
+    deserialize = "K: Deserialize<'de> + Eq + Hash, V: Deserialize<'de>"
+))]
+pub struct Pairs<K: Eq + Hash, V>(#[serde(with = "pairs")] pub HashMap<K, V>);
+
+impl<K: Eq + Hash, V> From<HashMap<K, V>> for Pairs<K, V> {
+    fn from(value: HashMap<K, V>) -> Self {
+        Self(value)
+    }
+}
+
+/// Encodes maps such as `NaiveStrategy` and `BehavioralStrategy` as lists of key-value pairs,
+/// for use with `#[serde(with = ...)]`
+pub mod pairs {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+    use std::collections::HashMap;
+    use std::hash::Hash;
+
+    pub fn serialize<K, V, S>(map: &HashMap<K, V>, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        K: Serialize,
+        V: Serialize,
+        S: Serializer,
+    {
+        serializer.collect_seq(map)
+    }
+
+    pub fn deserialize<'de, K, V, D>(deserializer: D) -> Result<HashMap<K, V>, D::Error>
+    where
+        K: Deserialize<'de> + Eq + Hash,
+        V: Deserialize<'de>,
+        D: Deserializer<'de>,
+    {
+        Ok(Vec::<(K, V)>::deserialize(deserializer)?
+            .into_iter()
+            .collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game_tree::sequence_form::SequenceFormSolution;
+    use crate::game_tree::strategy::NaiveStrategy;
+    use crate::game_tree::GameTree;
+    use crate::games::{CyclicGame, Host, MontyHall, Placement};
+    use crate::matrix_game::{BimatrixGame, PayoffMatrix};
+
+    #[test]
+    fn round_trips() {
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Host));
+        let solution = tree.solve_sequence_form();
+        let matrix = tree.to_matrix();
+
+        assert_eq!(
+            from_binary::<GameTree<_, _, _, _>>(&to_binary(&tree).unwrap()).unwrap(),
+            tree
+        );
+        assert_eq!(
+            from_json::<SequenceFormSolution<_, _, _>>(&to_json(&solution).unwrap()).unwrap(),
+            solution
+        );
+        assert_eq!(
+            from_json::<PayoffMatrix>(&to_json(&matrix).unwrap()).unwrap(),
+            matrix
+        );
+
+        let rules = CyclicGame::new(5).with_ties(vec![0., 1., 0., 0., 0.]);
+
+        assert_eq!(
+            from_json::<CyclicGame>(&to_json(&rules).unwrap()).unwrap(),
+            rules
+        );
+    }
+
+    #[test]
+    fn strategies_need_pairs_in_json() {
+        let tree = GameTree::from_rules(CyclicGame::default());
+        let strategy: NaiveStrategy<_, _> = tree.list_all_first_strategies().swap_remove(0);
+
+        assert!(matches!(to_json(&strategy), Err(MontyError::Parse(_))));
+
+        let json = to_json(&Pairs::from(strategy.clone())).unwrap();
+
+        assert_eq!(from_json::<Pairs<_, _>>(&json).unwrap(), Pairs(strategy));
+    }
+
+    #[test]
+    fn rejects_malformed_games() {
+        let ragged = r#"{"data":[[1,2],[3]],"rows":["a","b"],"cols":["x","y"]}"#;
+        let mislabelled = r#"{"data":[[1,2],[3,4]],"rows":["a"],"cols":["x","y"]}"#;
+        let json = |data: &str| format!("{{\"version\":{FORMAT_VERSION},\"data\":{data}}}");
+
+        assert!(matches!(
+            from_json::<PayoffMatrix>(&json(ragged)),
+            Err(MontyError::Parse(_))
+        ));
+        assert!(matches!(
+            from_json::<PayoffMatrix>(&json(mislabelled)),
+            Err(MontyError::Parse(_))
+        ));
+
+        let mismatched = r#"{
+            "first": {"data":[[1]],"rows":["a"],"cols":["x"]},
+            "second": {"data":[[1,2]],"rows":["a"],"cols":["x","y"]}
+        }"#;
+
+        assert!(matches!(
+            from_json::<BimatrixGame>(&json(mismatched)),
+            Err(MontyError::Parse(_))
+        ));
+
+        let tree = GameTree::from_rules(MontyHall::new(3, 1, Host::Standard, Placement::Random));
+        let valid = to_json(&tree).unwrap();
+        let cyclic = valid.replacen("{\"Prize\":0},1,", "{\"Prize\":0},0,", 1);
+        let unfair = valid.replacen("0.3333333333333333]", "0.5]", 1);
+
+        assert!(from_json::<GameTree<_, _, _, MontyHall>>(&valid).is_ok());
+        assert!(matches!(
+            from_json::<GameTree<_, _, _, MontyHall>>(&cyclic),
+            Err(MontyError::Parse(_))
+        ));
+        assert!(matches!(
+            from_json::<GameTree<_, _, _, MontyHall>>(&unfair),
+            Err(MontyError::Parse(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_other_versions() {
+        let json = to_json(&1.5)
+            .unwrap()
+            .replace(&format!("\"version\":{FORMAT_VERSION}"), "\"version\":0");
+
+        assert!(matches!(from_json::<f64>(&json), Err(MontyError::Parse(_))));
+    }
+}
@@ -0,0 +1,119 @@
+use std::process::{Command, Output};
+
+fn run(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_monty-hall"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+fn stdout(output: &Output) -> String {
+    String::from_utf8(output.stdout.clone()).unwrap()
+}
+
+fn stderr(output: &Output) -> String {
+    String::from_utf8(output.stderr.clone()).unwrap()
+}
+
+#[test]
+fn lists_the_games() {
+    let output = run(&["list"]);
+
+    assert_eq!(output.status.code(), Some(0));
+    assert!(stdout(&output).contains("monty-hall: the contestant against the host"));
+    assert!(stdout(&output).contains("--param doors=3 (integer)"));
+}
+
+#[test]
+fn matrices_as_csv_and_json() {
+    let csv = run(&["matrix", "rps", "--format", "csv"]);
+    let json = run(&["matrix", "guess", "--format", "json"]);
+
+    assert_eq!(csv.status.code(), Some(0));
+    assert_eq!(
+        stdout(&csv),
+        ",Second(2),Second(1),Second(0)\n\
+         First(2),0,1,-1\n\
+         First(1),-1,0,1\n\
+         First(0),1,-1,0\n"
+    );
+    assert_eq!(json.status.code(), Some(0));
+    assert_eq!(
+        stdout(&json),
+        "{\"rows\":[\"2\",\"1\",\"0\"],\"cols\":[\"(no moves)\"],\
+         \"payoffs\":[[0.5],[-1],[-0.5]]}\n"
+    );
+}
+
+#[test]
+fn solutions_as_csv_and_json() {
+    let csv = run(&["solve", "monty-hall", "--format", "csv"]);
+    let json = run(&["solve", "rps", "--format", "json"]);
+
+    assert_eq!(csv.status.code(), Some(0));
+
+    let csv = stdout(&csv);
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines[0], "player,strategy,probability");
+    assert!(lines[1].starts_with("value,,0.666666"));
+    assert_eq!(
+        lines.iter().filter(|x| x.starts_with("first,\"")).count(),
+        3
+    );
+    assert_eq!(lines[5], "second,(no moves),1");
+
+    assert_eq!(json.status.code(), Some(0));
+
+    let json = stdout(&json);
+
+    assert!(json.starts_with("{\"value\":"));
+    assert!(json.contains(",\"first\":[{\"strategy\":\"First(2)\",\"probability\":0.333"));
+    assert!(json.contains(",\"second\":[{\"strategy\":\"Second(2)\",\"probability\":0.333"));
+}
+
+#[test]
+fn trees_in_every_format() {
+    for (format, start) in [
+        ("dot", "digraph {"),
+        ("json", "{\"type\":\"first\""),
+        ("efg", "EFG 2 R"),
+    ] {
+        let output = run(&["tree", "rps", "--format", format]);
+
+        assert_eq!(output.status.code(), Some(0));
+        assert!(stdout(&output).starts_with(start), "{format}");
+    }
+}
+
+#[test]
+fn usage_errors_exit_with_two() {
+    for args in [
+        &[][..],
+        &["solve"],
+        &["solve", "nope"],
+        &["solve", "rps", "--format", "dot"],
+        &["tree", "rps", "--format", "csv"],
+        &["solve", "rps", "--format", "yaml"],
+        &["solve", "monty-hall", "--param", "doors=33"],
+        &["solve", "monty-hall", "--param", "doors"],
+        &["solve", "rps", "--verbose"],
+        &["solve", "rps", "kuhn-poker"],
+        &["frobnicate", "rps"],
+    ] {
+        let output = run(args);
+
+        assert_eq!(output.status.code(), Some(2), "{args:?}");
+        assert!(stdout(&output).is_empty(), "{args:?}");
+        assert!(stderr(&output).contains("usage:"), "{args:?}");
+    }
+}
+
+#[test]
+fn failures_exit_with_one() {
+    // An infinite twist leaves the payoffs of paper ties summing up to NaN
+    let output = run(&["solve", "twisted-rps", "--param", "twist=inf"]);
+
+    assert_eq!(output.status.code(), Some(1));
+    assert_eq!(stderr(&output), "error: the game is not zero-sum\n");
+}